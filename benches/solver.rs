@@ -0,0 +1,130 @@
+#![feature(test)]
+extern crate test;
+
+use hangman::{simulate, HangmanPlayer, SimOptions};
+use rand::{rngs::StdRng, seq::IndexedRandom, SeedableRng};
+use std::collections::{HashMap, HashSet};
+use test::{black_box, Bencher};
+
+const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+
+/// `count` made up words of each length in `lengths`, the same ones every run
+fn dictionary(count: usize, lengths: impl IntoIterator<Item = usize>) -> Vec<String> {
+    let mut rng = StdRng::seed_from_u64(0);
+    lengths
+        .into_iter()
+        .flat_map(|length| (0..count).map(move |_| length))
+        .map(|length| {
+            (0..length)
+                .map(|_| *ALPHABET.choose(&mut rng).unwrap() as char)
+                .collect()
+        })
+        .collect()
+}
+
+/// The first `n` distinct letters of `word`, each with the positions it's at
+fn guesses(word: &str, n: usize) -> Vec<(char, Vec<usize>)> {
+    let mut letters: Vec<char> = vec![];
+    for letter in word.chars() {
+        if !letters.contains(&letter) {
+            letters.push(letter);
+        }
+    }
+    assert!(
+        letters.len() >= n,
+        "{word} has fewer than {n} distinct letters"
+    );
+    letters
+        .into_iter()
+        .take(n)
+        .map(|letter| {
+            let positions = word.char_indices().filter(|&(_, c)| c == letter);
+            (letter, positions.map(|(i, _)| i).collect())
+        })
+        .collect()
+}
+
+#[bench]
+fn undo_restoring_frame(b: &mut Bencher) {
+    let words = dictionary(100_000, [6]);
+    let guesses = guesses(&words[0], 3);
+    let mut player = HangmanPlayer::new(&words, 6).unwrap();
+    for (letter, positions) in &guesses[..2] {
+        player.guess_letter(*letter, positions.clone()).unwrap();
+    }
+    let (letter, positions) = &guesses[2];
+    b.iter(|| {
+        player.guess_letter(*letter, positions.clone()).unwrap();
+        black_box(player.undo());
+    });
+}
+
+// how undo used to work: start over from the whole word list and prune it again
+#[bench]
+fn undo_replaying_from_start(b: &mut Bencher) {
+    let words = dictionary(100_000, [6]);
+    let guesses = guesses(&words[0], 3);
+    let mut player = HangmanPlayer::new(&words, 6).unwrap();
+    for (letter, positions) in &guesses[..2] {
+        player.guess_letter(*letter, positions.clone()).unwrap();
+    }
+    let (letter, positions) = &guesses[2];
+    b.iter(|| {
+        player.guess_letter(*letter, positions.clone()).unwrap();
+        player.reset();
+        for (letter, positions) in &guesses[..2] {
+            player.guess_letter(*letter, positions.clone()).unwrap();
+        }
+    });
+}
+
+#[bench]
+fn letter_scores_from_kept_counts(b: &mut Bencher) {
+    let words = dictionary(100_000, [6]);
+    let player = HangmanPlayer::new(&words, 6).unwrap();
+    b.iter(|| black_box(player.compute_letter_scores()));
+}
+
+// how letters used to be scored: count them over every remaining word each turn
+#[bench]
+fn letter_scores_from_recount(b: &mut Bencher) {
+    let words = dictionary(100_000, [6]);
+    let player = HangmanPlayer::new(&words, 6).unwrap();
+    b.iter(|| {
+        let mut counts: HashMap<char, usize> = HashMap::new();
+        for word in player.available_words() {
+            for letter in word.chars().collect::<HashSet<_>>() {
+                *counts.entry(letter).or_default() += 1;
+            }
+        }
+        let mut scores: Vec<_> = counts
+            .into_iter()
+            .filter(|(l, _)| !player.used_letters().contains(l))
+            .collect();
+        scores.sort_by(|(la, a), (lb, b)| b.cmp(a).then(la.cmp(lb)));
+        black_box(scores)
+    });
+}
+
+#[bench]
+fn simulate_borrowing_words(b: &mut Bencher) {
+    let words = dictionary(5_000, 4..16);
+    let options = SimOptions::default();
+    b.iter(|| {
+        for word in words.iter().step_by(5_000) {
+            black_box(simulate(&words, word, &options).unwrap());
+        }
+    });
+}
+
+// how simulate used to be called: with its own copy of the dictionary for every word
+#[bench]
+fn simulate_cloning_words(b: &mut Bencher) {
+    let words = dictionary(5_000, 4..16);
+    let options = SimOptions::default();
+    b.iter(|| {
+        for word in words.iter().step_by(5_000) {
+            black_box(simulate(&words.clone(), word, &options).unwrap());
+        }
+    });
+}
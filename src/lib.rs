@@ -0,0 +1,2399 @@
+#![feature(iterator_try_collect)]
+use std::{
+    cell::RefCell,
+    cmp::Reverse,
+    collections::{hash_map::Entry, BTreeMap, BTreeSet, HashMap, HashSet},
+    fs::{self, File},
+    hash::{DefaultHasher, Hash, Hasher},
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use clap::ValueEnum;
+use flate2::read::GzDecoder;
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// Everything that can go wrong in the solver
+#[derive(Debug, thiserror::Error)]
+pub enum HangmanError {
+    /// The dictionary has no words of the requested length; `available` holds the lengths it
+    /// does have, shortest first
+    #[error("{}", no_words_message(*.length, .available))]
+    NoWordsOfLength {
+        length: usize,
+        available: Vec<usize>,
+    },
+    /// Every word has been ruled out
+    #[error("No words left")]
+    NoCandidatesRemain,
+    /// Every letter has been guessed but more than one word remains
+    #[error("No letters left to guess")]
+    NoLettersLeft,
+    /// The solver narrowed a simulated game down to a word other than the answer
+    #[error("Final result '{0}' is not the correct word")]
+    WrongWord(String),
+    /// A guess result that contradicts what is already known about the word
+    #[error("{0}")]
+    Contradiction(String),
+    /// A word list couldn't be downloaded
+    #[error("{0}")]
+    DownloadFailed(String),
+    /// Malformed input, such as a bad frequencies file or a position outside the word
+    #[error("{0}")]
+    InvalidInput(String),
+    /// Reading or writing a file failed
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+fn no_words_message(length: usize, available: &[usize]) -> String {
+    match available.last() {
+        Some(&longest) if length > longest => format!(
+            "No {length}-letter words in dictionary, the longest words have {longest} letters"
+        ),
+        _ => format!(
+            "No {length}-letter words in dictionary (lengths available: {})",
+            available
+                .iter()
+                .map(usize::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// Wrap `reader` in a gzip decoder if its contents start with the gzip magic bytes
+fn decompressed<'a>(mut reader: impl BufRead + 'a) -> io::Result<Box<dyn BufRead + 'a>> {
+    if reader.fill_buf()?.starts_with(&[0x1f, 0x8b]) {
+        Ok(Box::new(BufReader::new(GzDecoder::new(reader))))
+    } else {
+        Ok(Box::new(reader))
+    }
+}
+
+/// Load words from `words_file`, downloading them from each of `word_sources` and caching the
+/// deduplicated union there if the file doesn't exist yet. Gzip-compressed files and downloads are
+/// decompressed transparently. Downloads are written to a temporary file that only replaces
+/// `words_file` once every source has been read, so a failed download never leaves a partial
+/// cache behind. A `words_file` of `-` reads the words from stdin instead, never downloading
+/// anything. If `verbose`, reports what it's doing on stderr.
+pub fn load_words(
+    words_file: &Path,
+    word_sources: &[String],
+    download: &DownloadOptions,
+    verbose: bool,
+) -> Result<Vec<String>, HangmanError> {
+    if words_file == Path::new("-") {
+        if verbose {
+            eprintln!("Loading from stdin");
+        }
+        let words: Vec<String> = decompressed(io::stdin().lock())?
+            .lines()
+            .filter(|line| line.as_ref().is_ok_and(|line| !line.is_empty()))
+            .try_collect()?;
+        if words.is_empty() {
+            Err(HangmanError::InvalidInput(
+                "No words were given on stdin".to_string(),
+            ))?;
+        }
+        Ok(words)
+    } else if let Some(file) = File::open(words_file).ok().filter(|_| !download.refresh) {
+        if verbose {
+            eprintln!("Loading from {:?}", words_file);
+        }
+        Ok(decompressed(BufReader::new(file))?.lines().try_collect()?)
+    } else {
+        let mut partial = words_file.as_os_str().to_owned();
+        partial.push(".part");
+        let partial = PathBuf::from(partial);
+        let words = download_words(&partial, words_file, word_sources, download, verbose)
+            .inspect_err(|_| {
+                let _ = fs::remove_file(&partial);
+            })?;
+        fs::rename(&partial, words_file)?;
+        Ok(words)
+    }
+}
+
+/// How [`load_words`] downloads word lists it doesn't have cached yet
+#[derive(Clone, Copy, Debug)]
+pub struct DownloadOptions {
+    /// Download even if the words file already exists, replacing it only once the download
+    /// succeeds
+    pub refresh: bool,
+    /// Times to retry a request that couldn't connect, timed out, or got a server error, waiting
+    /// twice as long before each retry as the last
+    pub retries: usize,
+    /// Give up on a request after this long
+    pub timeout: Option<Duration>,
+}
+
+impl Default for DownloadOptions {
+    fn default() -> DownloadOptions {
+        DownloadOptions {
+            refresh: false,
+            retries: 3,
+            timeout: Some(Duration::from_secs(30)),
+        }
+    }
+}
+
+/// Request `url`, retrying transient failures as configured by `options`
+fn fetch(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    options: &DownloadOptions,
+    verbose: bool,
+) -> Result<reqwest::blocking::Response, HangmanError> {
+    let mut delay = Duration::from_millis(500);
+    let mut attempt = 0;
+    loop {
+        let retry = attempt < options.retries;
+        let failure = match client.get(url).send() {
+            Ok(response) if response.status().is_success() => return Ok(response),
+            Ok(response)
+                if retry
+                    && (response.status().is_server_error()
+                        || response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS) =>
+            {
+                format!("{url} responded with {}", response.status())
+            }
+            Ok(response) => Err(HangmanError::DownloadFailed(format!(
+                "{url} responded with {}",
+                response.status()
+            )))?,
+            Err(e) if retry => format!("couldn't reach {url}: {e}"),
+            Err(e) => Err(HangmanError::DownloadFailed(format!(
+                "Couldn't reach {url} after {} attempts: {e}",
+                attempt + 1
+            )))?,
+        };
+        if verbose {
+            eprintln!("Download failed, {failure}; retrying in {delay:?}");
+        }
+        std::thread::sleep(delay);
+        delay *= 2;
+        attempt += 1;
+    }
+}
+
+/// Load the deduplicated union of every file matching the glob `pattern`, in path order. Gzip
+/// files are decompressed like in [`load_words`]. Nothing is downloaded or cached. If `verbose`,
+/// reports each file on stderr.
+pub fn load_words_glob(pattern: &str, verbose: bool) -> Result<Vec<String>, HangmanError> {
+    let mut paths: Vec<PathBuf> = glob::glob(pattern)
+        .map_err(|e| HangmanError::InvalidInput(format!("Invalid glob {pattern:?}: {e}")))?
+        .map(|path| path.map_err(io::Error::from))
+        .try_collect()?;
+    paths.sort();
+    if paths.is_empty() {
+        Err(HangmanError::InvalidInput(format!(
+            "No files match {pattern:?}"
+        )))?;
+    }
+    let mut seen = HashSet::new();
+    let mut words = Vec::new();
+    for path in paths {
+        if verbose {
+            eprintln!("Loading from {:?}", path);
+        }
+        for line in decompressed(BufReader::new(File::open(&path)?))?.lines() {
+            let line = line?;
+            if !line.is_empty() && seen.insert(line.clone()) {
+                words.push(line);
+            }
+        }
+    }
+    Ok(words)
+}
+
+/// Download the deduplicated union of `word_sources` into `partial`
+fn download_words(
+    partial: &Path,
+    words_file: &Path,
+    word_sources: &[String],
+    options: &DownloadOptions,
+    verbose: bool,
+) -> Result<Vec<String>, HangmanError> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(options.timeout)
+        .build()
+        .map_err(|e| HangmanError::DownloadFailed(e.to_string()))?;
+    let mut cache = BufWriter::new(File::create(partial)?);
+    let mut seen = HashSet::new();
+    let mut words = Vec::new();
+    for word_source in word_sources {
+        if verbose {
+            eprintln!(
+                "Downloading words from {} and saving to {:?}",
+                word_source, words_file
+            );
+        }
+        let response = fetch(&client, word_source, options, verbose)?;
+        for line in decompressed(BufReader::new(response))?.lines() {
+            let line = line?;
+            if seen.insert(line.clone()) {
+                cache.write_all(line.as_bytes())?;
+                cache.write_all(b"\n")?;
+                words.push(line);
+            }
+        }
+    }
+    cache.flush()?;
+    Ok(words)
+}
+
+/// Lowercase every word, dropping any that become duplicates of an earlier one
+pub fn lowercase_words(words: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    words
+        .into_iter()
+        .map(|word| word.to_lowercase())
+        .filter(|word| seen.insert(word.clone()))
+        .collect()
+}
+
+/// The distinct characters of `word`, in sorted order
+fn unique_letters(word: &str) -> Vec<char> {
+    let mut letters: Vec<_> = word.chars().collect();
+    letters.sort();
+    letters.dedup();
+    letters
+}
+
+/// How heavily `word` counts towards letter scores; words missing from the loaded frequencies
+/// count as if seen once
+pub fn word_weight(frequencies: &Option<Arc<Frequencies>>, word: &str) -> usize {
+    match frequencies {
+        Some(frequencies) => frequencies.get(word).copied().unwrap_or(0).max(1),
+        None => 1,
+    }
+}
+
+/// Count how many of `words` contain each of `letters`, with each word counted `weight(word)`
+/// times, sorted from most to least common with ties in alphabetical order
+pub fn count_letters(
+    words: &[String],
+    letters: impl Iterator<Item = char>,
+    weight: impl Fn(&str) -> usize,
+) -> Vec<(char, usize)> {
+    let mut counts: HashMap<_, _> = letters.map(|l| (l, 0usize)).collect();
+    for word in words.iter() {
+        let weight = weight(word);
+        for letter in unique_letters(word) {
+            if let Entry::Occupied(mut entry) = counts.entry(letter) {
+                *entry.get_mut() += weight;
+            }
+        }
+    }
+    let mut sorted_counts: Vec<_> = counts.into_iter().collect();
+    sorted_counts.sort_by(|(la, a), (lb, b)| b.cmp(a).then(la.cmp(lb)));
+
+    sorted_counts
+}
+
+/// Statistics about the words of a single length in a dictionary
+pub struct LengthStats {
+    pub length: usize,
+    pub words: usize,
+    pub top_first_letter: Option<char>,
+    /// Number of words containing each letter, sorted from most to least common
+    pub letter_counts: Vec<(char, usize)>,
+}
+
+/// The distinct alphabetic characters appearing in `words`, in sorted order
+pub fn alphabet(words: &[String]) -> Vec<char> {
+    let mut letters: Vec<_> = words
+        .iter()
+        .flat_map(|word| word.chars())
+        .filter(|c| c.is_alphabetic())
+        .collect();
+    letters.sort();
+    letters.dedup();
+    letters
+}
+
+/// Compute [`LengthStats`] for every word length present in `words`, shortest first
+pub fn analyze(words: &[String]) -> Vec<LengthStats> {
+    let letters = alphabet(words);
+    let mut by_length: HashMap<usize, Vec<String>> = HashMap::new();
+    for word in words {
+        by_length
+            .entry(word.chars().count())
+            .or_default()
+            .push(word.clone());
+    }
+    let mut stats: Vec<_> = by_length
+        .into_iter()
+        .map(|(length, words)| {
+            let mut first_letters: HashMap<char, usize> = HashMap::new();
+            for letter in words.iter().filter_map(|word| word.chars().next()) {
+                *first_letters.entry(letter).or_default() += 1;
+            }
+            LengthStats {
+                length,
+                words: words.len(),
+                top_first_letter: first_letters
+                    .into_iter()
+                    .max_by(|(la, a), (lb, b)| a.cmp(b).then(lb.cmp(la)))
+                    .map(|(letter, _)| letter),
+                letter_counts: count_letters(&words, letters.iter().copied(), |_| 1),
+            }
+        })
+        .collect();
+    stats.sort_by_key(|stats| stats.length);
+    stats
+}
+
+/// Number of occurrences of each word in some corpus, used to weight how likely each word is
+pub type Frequencies = HashMap<String, usize>;
+
+/// Keep only the `n` most common words of each length according to `frequencies`, or the first
+/// `n` of each length without them, leaving the kept words in their original order
+pub fn top_words(words: Vec<String>, n: usize, frequencies: Option<&Frequencies>) -> Vec<String> {
+    let mut by_length: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (i, word) in words.iter().enumerate() {
+        by_length.entry(word.chars().count()).or_default().push(i);
+    }
+    let mut keep = vec![false; words.len()];
+    for indices in by_length.values_mut() {
+        if let Some(frequencies) = frequencies {
+            indices.sort_by_key(|&i| Reverse(frequencies.get(&words[i]).copied().unwrap_or(0)));
+        }
+        for &i in indices.iter().take(n) {
+            keep[i] = true;
+        }
+    }
+    words
+        .into_iter()
+        .zip(keep)
+        .filter_map(|(word, keep)| keep.then_some(word))
+        .collect()
+}
+
+/// Load word frequencies from a file with one `word<TAB>count` entry per line
+pub fn load_frequencies(path: &Path) -> Result<Frequencies, HangmanError> {
+    BufReader::new(File::open(path)?)
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let line = line?;
+            let malformed = || {
+                HangmanError::InvalidInput(format!(
+                    "Line {} of {path:?} is not in `word<TAB>count` format",
+                    i + 1
+                ))
+            };
+            let (word, count) = line.split_once('\t').ok_or_else(malformed)?;
+            let count = count.trim().parse().map_err(|_| malformed())?;
+            Ok((word.to_string(), count))
+        })
+        .try_collect()
+}
+
+/// Solver state for a single hangman game of a fixed word length
+pub struct HangmanPlayer {
+    word_list: Vec<String>,
+    /// Words ruled out with [`HangmanPlayer::reject_word`], which stay out of `available_words`,
+    /// even across undos, until the game is reset
+    rejected: Vec<String>,
+    frequencies: Option<Arc<Frequencies>>,
+    /// Seed that tied letters are shuffled by, together with the letters guessed so far
+    seed: Option<u64>,
+    score_by: ScoreBy,
+    position_bias: PositionBias,
+    scoring: Box<dyn ScoringStrategy>,
+    /// Number of remaining words at or below which letters are scored by [`SplitScoring`]
+    bigram_threshold: Option<usize>,
+    /// Precomputed first guess, used instead of scoring letters before anything is guessed
+    openers: Option<Vec<char>>,
+    /// Time spent in each phase, recorded only once enabled with [`HangmanPlayer::with_timing`]
+    timings: Option<RefCell<Timings>>,
+    score_cache: Option<Arc<ScoreCache>>,
+    alphabet: Vec<char>,
+    /// Weighted score of each letter over `available_words` according to `score_by`, kept up to
+    /// date as words are pruned
+    letter_counts: HashMap<char, usize>,
+    available_words: Vec<String>,
+    current_guess: Vec<Option<char>>,
+    not_present: Vec<char>,
+    used_letters: Vec<char>,
+    guess_history: Vec<HistoryFrame>,
+}
+
+impl std::fmt::Debug for HangmanPlayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HangmanPlayer")
+            .field("available_words.len()", &self.available_words.len())
+            .field("current_guess", &self.current_guess)
+            .field("not_present", &self.not_present)
+            // .field("guess_history", &self.guess_history)
+            .finish()
+    }
+}
+
+impl HangmanPlayer {
+    /// Create a player that considers every word in `words` with `word_length` letters. Fails if
+    /// there are no such words.
+    pub fn new(words: &[String], word_length: usize) -> Result<HangmanPlayer, HangmanError> {
+        let matching: Vec<String> = words
+            .iter()
+            .filter(|word| word.chars().count() == word_length)
+            .cloned()
+            .collect();
+        if matching.is_empty() {
+            let mut lengths: Vec<_> = words.iter().map(|word| word.chars().count()).collect();
+            lengths.sort();
+            lengths.dedup();
+            Err(HangmanError::NoWordsOfLength {
+                length: word_length,
+                available: lengths,
+            })?;
+        }
+        let words = matching;
+        let mut player = HangmanPlayer {
+            alphabet: alphabet(&words),
+            letter_counts: HashMap::new(),
+            available_words: words.clone(),
+            word_list: words,
+            rejected: vec![],
+            frequencies: None,
+            seed: None,
+            score_by: ScoreBy::default(),
+            position_bias: PositionBias::default(),
+            scoring: Box::new(FrequencyScoring),
+            bigram_threshold: None,
+            openers: None,
+            timings: None,
+            score_cache: None,
+            current_guess: vec![None; word_length],
+            not_present: vec![],
+            used_letters: vec![],
+            guess_history: vec![],
+        };
+        player.recount_letters();
+        Ok(player)
+    }
+
+    /// Weight words by `frequencies` when scoring letters, rather than treating every word as
+    /// equally likely
+    pub fn with_frequencies(mut self, frequencies: Arc<Frequencies>) -> HangmanPlayer {
+        self.frequencies = Some(frequencies);
+        self.recount_letters();
+        self
+    }
+
+    /// Score letters by `score_by` instead of by how many words contain them
+    pub fn with_score_by(mut self, score_by: ScoreBy) -> HangmanPlayer {
+        self.score_by = score_by;
+        self.recount_letters();
+        self
+    }
+
+    /// Weight the points letters score by where they are in each word, so the frequency strategy
+    /// favors revealing one end of the word first
+    pub fn with_position_bias(mut self, position_bias: PositionBias) -> HangmanPlayer {
+        self.position_bias = position_bias;
+        self.recount_letters();
+        self
+    }
+
+    /// Rank letters with `scoring` instead of [`FrequencyScoring`]
+    pub fn with_scoring(mut self, scoring: Box<dyn ScoringStrategy>) -> HangmanPlayer {
+        self.scoring = scoring;
+        self
+    }
+
+    /// Once `threshold` or fewer words remain, score letters with [`SplitScoring`] instead, since
+    /// single letter counts tend to tie when only a few words are left
+    pub fn with_bigram_threshold(mut self, threshold: usize) -> HangmanPlayer {
+        self.bigram_threshold = Some(threshold);
+        self
+    }
+
+    /// Record how long each call to score letters or prune words takes, retrievable with
+    /// [`HangmanPlayer::timings`]
+    pub fn with_timing(mut self) -> HangmanPlayer {
+        self.timings = Some(RefCell::default());
+        self
+    }
+
+    /// Look up the letter [`HangmanPlayer::best_guess`] picks in `cache` before scoring letters,
+    /// and store it there after, unless they're ranked by plain letter frequency. The cache should
+    /// only be shared between players with the same dictionary, scoring settings and seed.
+    pub fn with_score_cache(mut self, cache: Arc<ScoreCache>) -> HangmanPlayer {
+        self.score_cache = Some(cache);
+        self
+    }
+
+    /// Open the game with the letters in `openers`, as precomputed by [`compute_openers`], instead
+    /// of scoring every letter for the first guess
+    pub fn with_openers(mut self, openers: Vec<char>) -> HangmanPlayer {
+        self.openers = Some(openers);
+        self
+    }
+
+    /// Shuffle letters with tied scores using `seed`, instead of ordering them alphabetically
+    pub fn with_seed(mut self, seed: u64) -> HangmanPlayer {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Shuffle each run of equally scored letters in `scores` if a seed was given. The rng is
+    /// seeded afresh from the seed and the letters guessed so far, so the same state always breaks
+    /// ties the same way, however many times it's scored.
+    fn shuffle_ties<S: PartialEq>(&self, scores: &mut [(char, S)]) {
+        let Some(seed) = self.seed else {
+            return;
+        };
+        let mut used = self.used_letters.clone();
+        used.sort();
+        let mut hasher = DefaultHasher::new();
+        (seed, used).hash(&mut hasher);
+        let mut rng = StdRng::seed_from_u64(hasher.finish());
+        for run in scores.chunk_by_mut(|(_, a), (_, b)| a == b) {
+            run.shuffle(&mut rng);
+        }
+    }
+
+    fn weight(&self, word: &str) -> usize {
+        word_weight(&self.frequencies, word)
+    }
+
+    /// Recount `letter_counts` from scratch after `available_words` is replaced
+    fn recount_letters(&mut self) {
+        let mut counts: HashMap<_, _> = self.alphabet.iter().map(|&l| (l, 0usize)).collect();
+        for word in self.available_words.iter() {
+            let weight = self.weight(word);
+            for (letter, n) in self.score_by.letter_points(word, self.position_bias) {
+                if let Some(count) = counts.get_mut(&letter) {
+                    *count += weight * n;
+                }
+            }
+        }
+        self.letter_counts = counts;
+    }
+
+    /// Words that are still consistent with every guess so far
+    pub fn available_words(&self) -> &[String] {
+        &self.available_words
+    }
+
+    /// Weighted score of each letter over the remaining words, as used by [`FrequencyScoring`]
+    pub fn letter_counts(&self) -> &HashMap<char, usize> {
+        &self.letter_counts
+    }
+
+    /// Letters that can be guessed, taken from the words being considered
+    pub fn alphabet(&self) -> &[char] {
+        &self.alphabet
+    }
+
+    /// Letters known at each position of the word
+    pub fn current_guess(&self) -> &[Option<char>] {
+        &self.current_guess
+    }
+
+    /// Letters known not to appear in the word
+    pub fn not_present(&self) -> &[char] {
+        &self.not_present
+    }
+
+    /// Every letter guessed so far, in order
+    pub fn used_letters(&self) -> &[char] {
+        &self.used_letters
+    }
+
+    /// Board state before each guess so far
+    pub fn history(&self) -> &[HistoryFrame] {
+        &self.guess_history
+    }
+
+    /// Snapshot of the guesses made so far, enough to restore the game with
+    /// [`HangmanPlayer::from_game_state`]
+    pub fn game_state(&self) -> GameState {
+        GameState {
+            current_guess: self.current_guess.clone(),
+            not_present: self.not_present.clone(),
+            used_letters: self.used_letters.clone(),
+            guess_history: self
+                .guess_history
+                .iter()
+                .map(|frame| HistoryFrame {
+                    available_words: None,
+                    ..frame.clone()
+                })
+                .collect(),
+        }
+    }
+
+    /// Restore a game saved with [`HangmanPlayer::game_state`], recomputing the remaining words
+    /// from `words`
+    pub fn from_game_state(
+        words: &[String],
+        state: GameState,
+    ) -> Result<HangmanPlayer, HangmanError> {
+        let mut player = HangmanPlayer::new(words, state.current_guess.len())?;
+        player.current_guess = state.current_guess;
+        player.not_present = state.not_present;
+        player.used_letters = state.used_letters;
+        player.guess_history = state.guess_history;
+        player.prune_and_fill_certain_letters();
+        Ok(player)
+    }
+
+    /// Top `n` unguessed letters ranked as in [`HangmanPlayer::compute_letter_scores`]
+    pub fn best_guesses(&self, n: usize) -> Vec<(char, usize)> {
+        let mut scores = self.compute_letter_scores();
+        scores.truncate(n);
+        scores
+    }
+
+    /// Rough lower bound on the yes/no questions still needed to single out the answer: the base 2
+    /// log of the number of remaining words
+    pub fn estimated_guesses_remaining(&self) -> f64 {
+        (self.available_words.len().max(1) as f64).log2()
+    }
+
+    /// Up to `n` words of the right length that come closest to matching the board, for when none
+    /// match exactly. Words are ranked by how many known letters they get wrong, then by how many
+    /// letters known to be missing they contain.
+    pub fn closest_words(&self, n: usize) -> Vec<&str> {
+        let mut ranked: Vec<_> = self
+            .word_list
+            .iter()
+            .filter(|word| !self.rejected.contains(word))
+            .map(|word| {
+                let wrong = word
+                    .chars()
+                    .zip(self.current_guess.iter())
+                    .filter(|(c, known)| known.is_some_and(|known| known != *c))
+                    .count();
+                let missing = word
+                    .chars()
+                    .filter(|c| self.not_present.contains(c))
+                    .count();
+                ((wrong, missing), word.as_str())
+            })
+            .collect();
+        ranked.sort();
+        ranked.into_iter().take(n).map(|(_, word)| word).collect()
+    }
+
+    /// Record that `letter` appears at `positions` (zero-indexed; empty if absent), and narrow
+    /// down the remaining words accordingly. Fails without changing anything if the result
+    /// contradicts what is already known about the word.
+    pub fn guess_letter(
+        &mut self,
+        letter: char,
+        positions: Vec<usize>,
+    ) -> Result<(), HangmanError> {
+        self.mark_result(letter, positions)?;
+        self.prune_and_fill_certain_letters();
+        Ok(())
+    }
+
+    /// Fill in letters already known at some positions, e.g. from a crossword crossing, and narrow
+    /// down the remaining words. Unlike [`HangmanPlayer::guess_letter`] this doesn't count as
+    /// guessing the letters, so they can still turn up in the blank positions. Fails without
+    /// changing anything if `known` is the wrong length or disagrees with the board.
+    pub fn fill_known_letters(&mut self, known: &[Option<char>]) -> Result<(), HangmanError> {
+        if known.len() != self.current_guess.len() {
+            Err(HangmanError::InvalidInput(format!(
+                "Expected {} known letters, got {}",
+                self.current_guess.len(),
+                known.len()
+            )))?;
+        }
+        for (i, (current, known)) in self.current_guess.iter().zip(known).enumerate() {
+            if let (Some(current), Some(known)) = (current, known) {
+                if current != known {
+                    Err(HangmanError::Contradiction(format!(
+                        "Position {} is already known to be {current}",
+                        i + 1
+                    )))?;
+                }
+            }
+        }
+        for (current, known) in self.current_guess.iter_mut().zip(known) {
+            if known.is_some() {
+                *current = *known;
+            }
+        }
+        self.prune_and_fill_certain_letters();
+        Ok(())
+    }
+
+    /// Forget every guess made so far, going back to the start of the game with every word of
+    /// this length possible again, including any that were rejected
+    pub fn reset(&mut self) {
+        self.current_guess = vec![None; self.current_guess.len()];
+        self.not_present.clear();
+        self.used_letters.clear();
+        self.guess_history.clear();
+        self.rejected.clear();
+        self.available_words = self.word_list.clone();
+        self.recount_letters();
+    }
+
+    /// Stop considering `word` until the game is reset, e.g. because the answer turned out not to
+    /// be it
+    pub fn reject_word(&mut self, word: &str) {
+        if !self.rejected.iter().any(|w| w == word) {
+            self.rejected.push(word.to_string());
+        }
+        self.available_words.retain(|w| w != word);
+        self.recount_letters();
+        self.prune_and_fill_certain_letters();
+    }
+
+    /// Revert the most recent guess, returning the state from before the undo so it can later be
+    /// passed to [`HangmanPlayer::redo`], or `None` if there was nothing to undo
+    pub fn undo(&mut self) -> Option<HistoryFrame> {
+        let frame = self.guess_history.pop()?;
+        let undone = self.snapshot();
+        self.restore(frame);
+        Some(undone)
+    }
+
+    fn has_known_letters(&self) -> bool {
+        self.current_guess.iter().any(Option::is_some)
+    }
+
+    fn unrejected_words(&self) -> Vec<String> {
+        self.word_list
+            .iter()
+            .filter(|word| !self.rejected.contains(word))
+            .cloned()
+            .collect()
+    }
+
+    /// Reapply a state returned by [`HangmanPlayer::undo`]
+    pub fn redo(&mut self, frame: HistoryFrame) {
+        self.push_history();
+        self.restore(frame);
+    }
+
+    fn snapshot(&self) -> HistoryFrame {
+        HistoryFrame {
+            guess: self.current_guess.clone(),
+            not_present: self.not_present.clone(),
+            used_letters: self.used_letters.clone(),
+            // before anything is known every word that hasn't been rejected is possible
+            available_words: (!self.used_letters.is_empty() || self.has_known_letters())
+                .then(|| self.available_words.clone()),
+        }
+    }
+
+    fn restore(&mut self, frame: HistoryFrame) {
+        self.current_guess = frame.guess;
+        self.not_present = frame.not_present;
+        self.used_letters = frame.used_letters;
+        match frame.available_words {
+            Some(mut words) => {
+                // the frame may be from before a word was rejected
+                if !self.rejected.is_empty() {
+                    words.retain(|word| !self.rejected.contains(word));
+                }
+                self.available_words = words;
+                self.recount_letters();
+            }
+            None if self.used_letters.is_empty() && !self.has_known_letters() => {
+                self.available_words = self.unrejected_words();
+                self.recount_letters();
+            }
+            None => {
+                self.available_words = self.unrejected_words();
+                self.recount_letters();
+                self.prune_and_fill_certain_letters();
+            }
+        }
+    }
+
+    /// Unguessed letters ranked by the player's [`ScoringStrategy`]; by default, how many remaining
+    /// words contain them, or how many blanks they fill across those words when scoring by
+    /// [`ScoreBy::Coverage`]. Below the bigram threshold, [`SplitScoring`] is used instead. Ties
+    /// are ordered alphabetically, or shuffled if a seed was given.
+    pub fn compute_letter_scores(&self) -> Vec<(char, usize)> {
+        let start = Instant::now();
+        let scores = match self.bigram_threshold {
+            Some(threshold) if self.available_words.len() <= threshold => SplitScoring.score(self),
+            _ => self.scoring.score(self),
+        };
+        let mut scores: Vec<_> = scores
+            .into_iter()
+            .filter(|(l, _)| !self.used_letters.contains(l))
+            .collect();
+        scores.sort_by(|(la, a), (lb, b)| b.cmp(a).then(la.cmp(lb)));
+        self.shuffle_ties(&mut scores);
+        self.record_time(start, |timings| &mut timings.scoring);
+        scores
+    }
+
+    /// The remaining word most likely to be the answer: the most frequent one if frequencies are
+    /// loaded, otherwise the one whose unguessed letters have the highest combined score
+    pub fn most_likely_word(&self) -> Option<&str> {
+        self.words_by_likelihood().first().copied()
+    }
+
+    /// The remaining words from most to least likely, ranked as in
+    /// [`HangmanPlayer::most_likely_word`]
+    pub fn words_by_likelihood(&self) -> Vec<&str> {
+        let mut words: Vec<_> = self.available_words.iter().map(String::as_str).collect();
+        if self.frequencies.is_some() {
+            words.sort_by_key(|word| Reverse(self.weight(word)));
+            return words;
+        }
+        let scores: HashMap<char, usize> = self.compute_letter_scores().into_iter().collect();
+        words.sort_by_cached_key(|word| {
+            Reverse(
+                unique_letters(word)
+                    .into_iter()
+                    .filter_map(|letter| scores.get(&letter))
+                    .sum::<usize>(),
+            )
+        });
+        words
+    }
+
+    /// Unguessed letters ranked by the entropy of the split their result would produce. Ties are
+    /// ordered alphabetically, or shuffled if a seed was given.
+    pub fn compute_letter_entropies(&self) -> Vec<(char, f64)> {
+        let start = Instant::now();
+        let total: f64 = self
+            .available_words
+            .iter()
+            .map(|word| self.weight(word) as f64)
+            .sum();
+        let mut entropies: Vec<_> = self
+            .alphabet
+            .iter()
+            .copied()
+            .filter(|l| !self.used_letters.contains(l))
+            .map(|letter| {
+                // ordered, so the floating point sum below comes out the same every run
+                let mut partitions: BTreeMap<Vec<usize>, usize> = BTreeMap::new();
+                for word in self.available_words.iter() {
+                    let positions = word
+                        .chars()
+                        .enumerate()
+                        .filter_map(|(i, c)| (c == letter).then_some(i))
+                        .collect();
+                    *partitions.entry(positions).or_default() += self.weight(word);
+                }
+                let entropy: f64 = partitions
+                    .into_values()
+                    .map(|count| {
+                        let p = count as f64 / total;
+                        -p * p.log2()
+                    })
+                    .sum();
+                (letter, entropy)
+            })
+            .collect();
+        entropies.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        self.shuffle_ties(&mut entropies);
+        self.record_time(start, |timings| &mut timings.scoring);
+
+        entropies
+    }
+
+    /// Unguessed letters paired with the share of remaining words containing them, weighted by
+    /// frequency if loaded: the chance guessing them doesn't cost a mistake. Sorted from safest to
+    /// riskiest, with equally safe letters ordered by entropy so the more informative one goes
+    /// first, then alphabetically, or shuffled if a seed was given.
+    pub fn compute_letter_safety(&self) -> Vec<(char, f64)> {
+        let entropies: HashMap<char, f64> = self.compute_letter_entropies().into_iter().collect();
+        let total: f64 = self
+            .available_words
+            .iter()
+            .map(|word| self.weight(word) as f64)
+            .sum();
+        let mut safeties: Vec<_> = self
+            .alphabet
+            .iter()
+            .copied()
+            .filter(|l| !self.used_letters.contains(l))
+            .map(|letter| {
+                let containing: usize = self
+                    .available_words
+                    .iter()
+                    .filter(|word| word.contains(letter))
+                    .map(|word| self.weight(word))
+                    .sum();
+                (letter, (containing as f64 / total, entropies[&letter]))
+            })
+            .collect();
+        safeties.sort_by(|(la, a), (lb, b)| {
+            b.0.total_cmp(&a.0)
+                .then(b.1.total_cmp(&a.1))
+                .then(la.cmp(lb))
+        });
+        self.shuffle_ties(&mut safeties);
+
+        safeties
+            .into_iter()
+            .map(|(letter, (safety, _))| (letter, safety))
+            .collect()
+    }
+
+    /// Unguessed letters paired with the most words that could remain after guessing them, over
+    /// every position set the letter could turn up in, sorted from smallest to largest. Ties are
+    /// ordered alphabetically, or shuffled if a seed was given.
+    pub fn compute_letter_minimax(&self) -> Vec<(char, usize)> {
+        let start = Instant::now();
+        let mut worst_cases: Vec<_> = self
+            .alphabet
+            .iter()
+            .copied()
+            .filter(|l| !self.used_letters.contains(l))
+            .map(|letter| {
+                let mut partitions: HashMap<Vec<usize>, usize> = HashMap::new();
+                for word in self.available_words.iter() {
+                    let positions = word
+                        .chars()
+                        .enumerate()
+                        .filter_map(|(i, c)| (c == letter).then_some(i))
+                        .collect();
+                    *partitions.entry(positions).or_default() += 1;
+                }
+                (letter, partitions.into_values().max().unwrap_or(0))
+            })
+            .collect();
+        worst_cases.sort_by(|(la, a), (lb, b)| a.cmp(b).then(la.cmp(lb)));
+        self.shuffle_ties(&mut worst_cases);
+        self.record_time(start, |timings| &mut timings.scoring);
+
+        worst_cases
+    }
+
+    /// Whether guessing any unguessed letter could rule out some of the remaining words. When
+    /// this is false but several words remain, they only differ in ways no guess can reveal.
+    pub fn has_informative_letters(&self) -> bool {
+        let positions = |word: &str, letter| -> Vec<usize> {
+            word.chars()
+                .enumerate()
+                .filter_map(|(i, c)| (c == letter).then_some(i))
+                .collect()
+        };
+        let Some((first, rest)) = self.available_words.split_first() else {
+            return false;
+        };
+        self.alphabet
+            .iter()
+            .filter(|l| !self.used_letters.contains(l))
+            .any(|&letter| {
+                let first = positions(first, letter);
+                rest.iter().any(|word| positions(word, letter) != first)
+            })
+    }
+
+    /// The positions an adversary would say `letter` is at to keep as many of the remaining
+    /// words possible as it can, revealing as few positions as possible among equally large
+    /// families. A letter no remaining word has gets no positions.
+    pub fn largest_family(&self, letter: char) -> Vec<usize> {
+        let mut families: BTreeMap<Vec<usize>, usize> = BTreeMap::new();
+        for word in self.available_words.iter() {
+            let positions = word
+                .chars()
+                .enumerate()
+                .filter_map(|(i, c)| (c == letter).then_some(i))
+                .collect();
+            *families.entry(positions).or_default() += 1;
+        }
+        families
+            .into_iter()
+            .max_by(|(pa, a), (pb, b)| a.cmp(b).then(pb.len().cmp(&pa.len())).then(pb.cmp(pa)))
+            .map(|(positions, _)| positions)
+            .unwrap_or_default()
+    }
+
+    /// Unguessed letters in the order `strategy` ranks them, best first
+    pub fn ranked_letters(&self, strategy: Strategy) -> Vec<char> {
+        match strategy {
+            Strategy::Frequency => self
+                .compute_letter_scores()
+                .into_iter()
+                .map(|(l, _)| l)
+                .collect(),
+            Strategy::Entropy => self
+                .compute_letter_entropies()
+                .into_iter()
+                .map(|(l, _)| l)
+                .collect(),
+            Strategy::Minimax => self
+                .compute_letter_minimax()
+                .into_iter()
+                .map(|(l, _)| l)
+                .collect(),
+            Strategy::Safe => self
+                .compute_letter_safety()
+                .into_iter()
+                .map(|(l, _)| l)
+                .collect(),
+        }
+    }
+
+    /// The precomputed opening letters, if any were given and nothing has been guessed yet
+    pub fn openers(&self) -> Option<&[char]> {
+        self.openers
+            .as_deref()
+            .filter(|_| self.used_letters.is_empty())
+    }
+
+    /// The letter `strategy` would guess next, or `None` if every letter has been guessed
+    pub fn best_guess(&self, strategy: Strategy) -> Option<char> {
+        if let Some(&opener) = self.openers().and_then(|openers| openers.first()) {
+            return Some(opener);
+        }
+        // plain frequency scores are kept up to date as words are pruned, so they're cheaper to
+        // rank than to look up
+        let split_scoring = self
+            .bigram_threshold
+            .is_some_and(|threshold| self.available_words.len() <= threshold);
+        let cache = self
+            .score_cache
+            .as_ref()
+            .filter(|_| strategy != Strategy::Frequency || split_scoring);
+        let score = || match strategy {
+            Strategy::Frequency => self.compute_letter_scores().first().map(|&(l, _)| l),
+            Strategy::Entropy => self.compute_letter_entropies().first().map(|&(l, _)| l),
+            Strategy::Minimax => self.compute_letter_minimax().first().map(|&(l, _)| l),
+            Strategy::Safe => self.compute_letter_safety().first().map(|&(l, _)| l),
+        };
+        let Some(cache) = cache else {
+            return score();
+        };
+        let signature = self.state_signature(strategy);
+        cache.get(signature).unwrap_or_else(|| {
+            let best = score();
+            cache.insert(signature, best);
+            best
+        })
+    }
+
+    /// Hash of the remaining words and the letters guessed so far, which together settle how
+    /// `strategy` ranks the unguessed letters
+    fn state_signature(&self, strategy: Strategy) -> u64 {
+        let mut used = self.used_letters.clone();
+        used.sort();
+        let mut hasher = DefaultHasher::new();
+        (strategy, used, &self.available_words).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// The letter `strategy` would guess next, the same one [`HangmanPlayer::best_guess`] gives.
+    /// Ties are shuffled afresh from the seed and the letters guessed so far, so peeking leaves
+    /// nothing changed for the guess actually made.
+    pub fn peek_best_guess(&self, strategy: Strategy) -> Option<char> {
+        self.best_guess(strategy)
+    }
+
+    /// How many of the remaining words would be left if `letter` turned out to be at exactly
+    /// `positions`, without changing the board. Results that contradict the board leave none.
+    pub fn simulate_guess(&self, letter: char, positions: &[usize]) -> usize {
+        if self.check_result(letter, positions).is_err() {
+            return 0;
+        }
+        let mut current_guess = self.current_guess.clone();
+        let mut not_present = self.not_present.clone();
+        let mut revealed = self.revealed_letters();
+        if positions.is_empty() {
+            not_present.push(letter);
+        } else {
+            revealed.push(letter);
+            for &pos in positions {
+                current_guess[pos] = Some(letter);
+            }
+        }
+        let not_at = excluded_positions(&current_guess, &revealed);
+        let mut potential_letters = vec![vec![]; current_guess.len()];
+        self.available_words
+            .iter()
+            .filter(|word| {
+                Self::matches_guess(
+                    word,
+                    &current_guess,
+                    &not_present,
+                    &not_at,
+                    self.alphabet.len(),
+                    &mut potential_letters,
+                )
+            })
+            .count()
+    }
+
+    /// History with the remaining words of each frame dropped, for results that never need to be
+    /// undone
+    fn into_history(self) -> Vec<HistoryFrame> {
+        self.guess_history
+            .into_iter()
+            .map(|frame| HistoryFrame {
+                available_words: None,
+                ..frame
+            })
+            .collect()
+    }
+
+    /// Results of a game that ends at the current board, without consuming the player as
+    /// [`HangmanPlayer::into_history`] does
+    fn tree_results(&self, mistakes: usize, won: bool) -> SimResults {
+        let frame = HistoryFrame {
+            available_words: None,
+            ..self.snapshot()
+        };
+        let mut history: Vec<_> = self
+            .guess_history
+            .iter()
+            .map(|frame| HistoryFrame {
+                available_words: None,
+                ..frame.clone()
+            })
+            .collect();
+        history.push(frame);
+        let mut remaining = self.remaining_after_guesses();
+        remaining.push(self.available_words.len());
+        SimResults {
+            history,
+            remaining,
+            guesses: self.used_letters.clone(),
+            mistakes,
+            won,
+            timings: None,
+        }
+    }
+
+    /// How many words were left after each guess, read from the history frames after the first
+    fn remaining_after_guesses(&self) -> Vec<usize> {
+        self.guess_history
+            .iter()
+            .skip(1)
+            .map(|frame| frame.available_words.as_ref().map_or(0, Vec::len))
+            .collect()
+    }
+
+    fn push_history(&mut self) {
+        let frame = self.snapshot();
+        self.guess_history.push(frame);
+    }
+
+    /// Check that `letter` appearing at exactly `positions` is consistent with the board
+    fn check_result(&self, letter: char, positions: &[usize]) -> Result<(), HangmanError> {
+        if positions.is_empty() {
+            if self.current_guess.contains(&Some(letter)) {
+                Err(HangmanError::Contradiction(format!(
+                    "Letter {letter} is already known to be in the word, that contradicts a previous guess"
+                )))?;
+            }
+            return Ok(());
+        }
+        if self.not_present.contains(&letter) {
+            Err(HangmanError::Contradiction(format!(
+                "Letter {letter} is already known not to be in the word, that contradicts a previous guess"
+            )))?;
+        }
+        for &pos in positions {
+            match self.current_guess.get(pos) {
+                None => Err(HangmanError::InvalidInput(format!(
+                    "Position {} is outside the word",
+                    pos + 1
+                )))?,
+                Some(Some(placed)) if *placed != letter => Err(HangmanError::Contradiction(format!(
+                    "Position {} is already known to be {placed}, that contradicts a previous guess",
+                    pos + 1
+                )))?,
+                _ => {}
+            }
+        }
+        if let Some(pos) = (0..self.current_guess.len())
+            .find(|pos| self.current_guess[*pos] == Some(letter) && !positions.contains(pos))
+        {
+            Err(HangmanError::Contradiction(format!(
+                "Letter {letter} is already known to be at position {}, that contradicts a previous guess",
+                pos + 1
+            )))?;
+        }
+        Ok(())
+    }
+
+    fn mark_result(&mut self, letter: char, positions: Vec<usize>) -> Result<(), HangmanError> {
+        self.check_result(letter, &positions)?;
+        self.push_history();
+
+        self.used_letters.push(letter);
+        if positions.is_empty() {
+            self.not_present.push(letter);
+        } else {
+            for pos in positions {
+                self.current_guess[pos] = Some(letter);
+            }
+        }
+        Ok(())
+    }
+
+    /// Guessed letters that are in the word. Guessing a letter reveals all of its positions, so
+    /// they can't be in any blank position. Letters filled in only because every remaining word
+    /// had them there aren't included, since they may still turn up elsewhere.
+    fn revealed_letters(&self) -> Vec<char> {
+        self.used_letters
+            .iter()
+            .filter(|letter| !self.not_present.contains(letter))
+            .copied()
+            .collect()
+    }
+
+    /// Letters known not to be at each position: every revealed letter for a position that is
+    /// still blank, as a guess shows every position its letter is at, and none for a filled one
+    pub fn not_at(&self) -> Vec<Vec<char>> {
+        excluded_positions(&self.current_guess, &self.revealed_letters())
+    }
+
+    fn prune_words(&mut self) -> Vec<Vec<char>> {
+        let not_at = self.not_at();
+        let mut potential_letters = vec![vec![]; self.current_guess.len()];
+        let letter_counts = &mut self.letter_counts;
+        let frequencies = &self.frequencies;
+        let score_by = self.score_by;
+        let position_bias = self.position_bias;
+
+        self.available_words.retain(|word| {
+            let keep = Self::matches_guess(
+                word,
+                &self.current_guess,
+                &self.not_present,
+                &not_at,
+                self.alphabet.len(),
+                &mut potential_letters,
+            );
+            if !keep {
+                let weight = word_weight(frequencies, word);
+                for (letter, n) in score_by.letter_points(word, position_bias) {
+                    if let Some(count) = letter_counts.get_mut(&letter) {
+                        *count -= weight * n;
+                    }
+                }
+            }
+            keep
+        });
+        potential_letters
+    }
+
+    /// Whether `word` is consistent with the known letters, recording the letters it could place
+    /// in each blank position into `potential_letters` if so, until a position has all
+    /// `alphabet_len` letters. Words of the wrong length never match, and neither do words with a
+    /// letter at a position `not_at` excludes it from.
+    fn matches_guess(
+        word: &str,
+        current_guess: &[Option<char>],
+        not_present: &[char],
+        not_at: &[Vec<char>],
+        alphabet_len: usize,
+        potential_letters: &mut [Vec<char>],
+    ) -> bool {
+        if word.chars().count() != current_guess.len() {
+            return false;
+        }
+        let mut potential_additions = vec![vec![]; current_guess.len()];
+        for (
+            ((potential_place_additions, potential_place_letters), (word_letter, guess_letter)),
+            excluded,
+        ) in (potential_additions.iter_mut().zip(potential_letters.iter()))
+            .zip(word.chars().zip(current_guess.iter()))
+            .zip(not_at)
+        {
+            if not_present.contains(&word_letter) || excluded.contains(&word_letter) {
+                return false;
+            }
+            match guess_letter {
+                Some(placed_letter) => {
+                    if placed_letter != &word_letter {
+                        return false;
+                    }
+                }
+                None if potential_place_letters.len() < alphabet_len => {
+                    potential_place_additions.push(word_letter)
+                }
+                _ => {}
+            }
+        }
+        for (potential_place_additions, potential_place_letters) in potential_additions
+            .into_iter()
+            .zip(potential_letters.iter_mut())
+        {
+            for letter_addition in potential_place_additions {
+                if !potential_place_letters.contains(&letter_addition) {
+                    potential_place_letters.push(letter_addition);
+                }
+            }
+        }
+        true
+    }
+
+    fn fill_certain_letters(&mut self, potential_letters: Vec<Vec<char>>) {
+        for (guess_letter, potential_letter) in self.current_guess.iter_mut().zip(potential_letters)
+        {
+            if let (None, &[letter]) = (&guess_letter, &potential_letter[..]) {
+                *guess_letter = Some(letter);
+            }
+        }
+    }
+
+    /// Time spent so far in each phase, if timing was enabled
+    pub fn timings(&self) -> Option<Timings> {
+        self.timings
+            .as_ref()
+            .map(|timings| timings.borrow().clone())
+    }
+
+    fn record_time(&self, start: Instant, phase: impl FnOnce(&mut Timings) -> &mut Vec<Duration>) {
+        if let Some(timings) = &self.timings {
+            phase(&mut timings.borrow_mut()).push(start.elapsed());
+        }
+    }
+
+    fn prune_and_fill_certain_letters(&mut self) {
+        let start = Instant::now();
+        let potential_letters = self.prune_words();
+        self.record_time(start, |timings| &mut timings.pruning);
+        self.fill_certain_letters(potential_letters);
+    }
+}
+
+/// `revealed` for each blank position of `board`, and nothing for each filled one
+fn excluded_positions(board: &[Option<char>], revealed: &[char]) -> Vec<Vec<char>> {
+    board
+        .iter()
+        .map(|letter| match letter {
+            Some(_) => vec![],
+            None => revealed.to_vec(),
+        })
+        .collect()
+}
+
+/// Words in `words` that fit `pattern` (`None` for an unknown letter), containing none of
+/// `exclude` and all of `include`. As on a hangman board, each letter in `pattern` is taken to be
+/// shown at every position it occurs in the word.
+pub fn search<'a>(
+    words: &'a [String],
+    pattern: &[Option<char>],
+    exclude: &[char],
+    include: &[char],
+) -> Vec<&'a str> {
+    let revealed: Vec<char> = pattern.iter().flatten().copied().collect();
+    let not_at = excluded_positions(pattern, &revealed);
+    let mut potential_letters = vec![vec![]; pattern.len()];
+    words
+        .iter()
+        .filter(|word| include.iter().all(|&letter| word.contains(letter)))
+        .filter(|word| {
+            // the letters each blank could hold aren't needed, so none are recorded
+            HangmanPlayer::matches_guess(word, pattern, exclude, &not_at, 0, &mut potential_letters)
+        })
+        .map(String::as_str)
+        .collect()
+}
+
+/// Letters guessed by [`HangmanPlayer::best_guess`], remembered by a signature of the words still
+/// possible and the letters already guessed. Games against different words often reach the same
+/// state, so simulations sharing a cache only score each state once. Once `capacity` states are
+/// stored, the cache is emptied and starts filling again.
+pub struct ScoreCache {
+    capacity: usize,
+    entries: Mutex<HashMap<u64, Option<char>>>,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+impl ScoreCache {
+    /// Create an empty cache holding at most `capacity` states
+    pub fn new(capacity: usize) -> ScoreCache {
+        ScoreCache {
+            capacity,
+            entries: Mutex::default(),
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+        }
+    }
+
+    /// Number of lookups that found the state already scored
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Number of lookups that had to score the state
+    pub fn misses(&self) -> usize {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    fn get(&self, signature: u64) -> Option<Option<char>> {
+        let guess = self.entries.lock().unwrap().get(&signature).copied();
+        match guess {
+            Some(_) => self.hits.fetch_add(1, Ordering::Relaxed),
+            None => self.misses.fetch_add(1, Ordering::Relaxed),
+        };
+        guess
+    }
+
+    fn insert(&self, signature: u64, guess: Option<char>) {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= self.capacity {
+            entries.clear();
+        }
+        entries.insert(signature, guess);
+    }
+}
+
+/// Whether `mistakes` missed guesses lose the game. This is checked before whether the word has
+/// been found, so a game that finds the word with its last allowed miss is still lost.
+pub fn is_lost(mistakes: usize, max_mistakes: Option<usize>) -> bool {
+    max_mistakes.is_some_and(|max_mistakes| mistakes >= max_mistakes)
+}
+
+/// Settings controlling how [`simulate`] plays
+#[derive(Clone, Default)]
+pub struct SimOptions {
+    pub strategy: Strategy,
+    pub score_by: ScoreBy,
+    pub position_bias: PositionBias,
+    /// Lose the game once this many guesses miss
+    pub max_mistakes: Option<usize>,
+    pub frequencies: Option<Arc<Frequencies>>,
+    /// Seed used to shuffle tied letters, instead of picking the alphabetically first
+    pub seed: Option<u64>,
+    /// Precomputed first guesses to open with instead of scoring letters
+    pub openers: Option<Arc<Openers>>,
+    /// See [`HangmanPlayer::with_bigram_threshold`]
+    pub bigram_threshold: Option<usize>,
+    /// Record how long each phase of every turn takes into [`SimResults::timings`]
+    pub timing: bool,
+    /// Share scored states between games, see [`ScoreCache`]
+    pub score_cache: Option<Arc<ScoreCache>>,
+}
+
+/// Play a full game against `word`, recording each turn
+pub fn simulate(
+    words: &[String],
+    word: &str,
+    options: &SimOptions,
+) -> Result<SimResults, HangmanError> {
+    let SimOptions {
+        strategy,
+        max_mistakes,
+        timing,
+        ..
+    } = *options;
+    let mut player = sim_player(words, word.chars().count(), options)?;
+    if timing {
+        player = player.with_timing();
+    }
+    let mut mistakes = 0;
+    let mut guesses = Vec::new();
+
+    loop {
+        let letter = player
+            .best_guess(strategy)
+            .ok_or(HangmanError::NoLettersLeft)?; // simulate guess
+        let positions: Vec<_> = word
+            .chars()
+            .enumerate()
+            .filter_map(|(i, c)| (c == letter).then_some(i))
+            .collect(); // simulate receiving the result of the guess
+        if positions.is_empty() {
+            mistakes += 1;
+        }
+        guesses.push(letter);
+        player.mark_result(letter, positions)?;
+        player.prune_and_fill_certain_letters();
+        let won = match &player.available_words[..] {
+            _ if is_lost(mistakes, max_mistakes) => false,
+            [single] if single == word => true,
+            [] => Err(HangmanError::NoCandidatesRemain)?,
+            [single] => Err(HangmanError::WrongWord(single.clone()))?,
+            _ => continue,
+        };
+        player.push_history();
+        return Ok(SimResults {
+            timings: player.timings(),
+            remaining: player.remaining_after_guesses(),
+            history: player.into_history(),
+            guesses,
+            mistakes,
+            won,
+        });
+    }
+}
+
+/// Simulate every word in `targets`, which must all have `length` letters, passing each word's
+/// results to `on_result` in no particular order. The results are exactly those [`simulate`]
+/// would give, but rather than replaying every game from scratch, the games are walked together
+/// as a tree: words that have given the same result for every guess so far share one player, so
+/// each guess is only scored once per branch instead of once per word. [`SimOptions::timing`] is
+/// ignored.
+pub fn simulate_tree(
+    words: &[String],
+    length: usize,
+    targets: &[&str],
+    options: &SimOptions,
+    on_result: &mut impl FnMut(&str, Result<SimResults, String>),
+) {
+    match sim_player(words, length, options) {
+        Ok(mut player) => walk_tree(&mut player, targets.to_vec(), 0, options, on_result),
+        Err(e) => {
+            for target in targets {
+                on_result(target, Err(e.to_string()));
+            }
+        }
+    }
+}
+
+/// Guess the next letter for every word in `targets`, all of which are consistent with the
+/// board so far, then recurse into each group of words that give the same result. `player` is
+/// left as it was found.
+fn walk_tree(
+    player: &mut HangmanPlayer,
+    targets: Vec<&str>,
+    mistakes: usize,
+    options: &SimOptions,
+    on_result: &mut impl FnMut(&str, Result<SimResults, String>),
+) {
+    let Some(letter) = player.best_guess(options.strategy) else {
+        for target in targets {
+            on_result(target, Err(HangmanError::NoLettersLeft.to_string()));
+        }
+        return;
+    };
+    let mut branches: BTreeMap<Vec<usize>, Vec<&str>> = BTreeMap::new();
+    for target in targets {
+        let positions = target
+            .chars()
+            .enumerate()
+            .filter_map(|(i, c)| (c == letter).then_some(i))
+            .collect();
+        branches.entry(positions).or_default().push(target);
+    }
+    for (positions, branch) in branches {
+        let mistakes = mistakes + positions.is_empty() as usize;
+        if let Err(e) = player.mark_result(letter, positions) {
+            for target in branch {
+                on_result(target, Err(e.to_string()));
+            }
+            continue;
+        }
+        player.prune_and_fill_certain_letters();
+        let lost = is_lost(mistakes, options.max_mistakes);
+        let mut remaining = Vec::new();
+        for target in branch {
+            match &player.available_words[..] {
+                _ if lost => on_result(target, Ok(player.tree_results(mistakes, false))),
+                [single] if single == target => {
+                    on_result(target, Ok(player.tree_results(mistakes, true)))
+                }
+                [] => on_result(target, Err(HangmanError::NoCandidatesRemain.to_string())),
+                [single] => on_result(
+                    target,
+                    Err(HangmanError::WrongWord(single.clone()).to_string()),
+                ),
+                _ => remaining.push(target),
+            }
+        }
+        if !remaining.is_empty() {
+            walk_tree(player, remaining, mistakes, options, on_result);
+        }
+        player.undo();
+    }
+}
+
+/// The guesses `options.strategy` would make for words of `length` letters, branching on every
+/// outcome that's possible in the dictionary, down to `max_depth` guesses.
+pub fn guess_tree(
+    words: &[String],
+    length: usize,
+    options: &SimOptions,
+    max_depth: usize,
+) -> Result<GuessTree, HangmanError> {
+    let mut player = sim_player(words, length, options)?;
+    Ok(build_guess_tree(&mut player, options.strategy, max_depth))
+}
+
+/// The guesses `options.strategy` makes for words of `length` letters if every one of them misses.
+/// Stops once a single word is left, or with the first guess that no word is missing, as that one
+/// can't miss.
+pub fn miss_guesses(
+    words: &[String],
+    length: usize,
+    options: &SimOptions,
+) -> Result<Vec<char>, HangmanError> {
+    let mut player = sim_player(words, length, options)?;
+    let mut guesses = Vec::new();
+    while player.available_words.len() > 1 {
+        let Some(letter) = player.best_guess(options.strategy) else {
+            break;
+        };
+        guesses.push(letter);
+        if player.simulate_guess(letter, &[]) == 0 {
+            break;
+        }
+        player.guess_letter(letter, vec![])?;
+    }
+    Ok(guesses)
+}
+
+fn build_guess_tree(player: &mut HangmanPlayer, strategy: Strategy, depth: usize) -> GuessTree {
+    let mut tree = GuessTree {
+        remaining: player.available_words.len(),
+        word: match &player.available_words[..] {
+            [word] => Some(word.clone()),
+            _ => None,
+        },
+        guess: None,
+        branches: vec![],
+    };
+    if depth == 0 || tree.remaining <= 1 {
+        return tree;
+    }
+    let Some(letter) = player.best_guess(strategy) else {
+        return tree;
+    };
+    let outcomes: BTreeSet<Vec<usize>> = player
+        .available_words
+        .iter()
+        .map(|word| {
+            word.chars()
+                .enumerate()
+                .filter_map(|(i, c)| (c == letter).then_some(i))
+                .collect()
+        })
+        .collect();
+    tree.guess = Some(letter);
+    for positions in outcomes {
+        if player.mark_result(letter, positions.clone()).is_err() {
+            continue;
+        }
+        player.prune_and_fill_certain_letters();
+        tree.branches.push(GuessBranch {
+            positions,
+            tree: build_guess_tree(player, strategy, depth - 1),
+        });
+        player.undo();
+    }
+    tree
+}
+
+/// Player set up the way [`simulate`] plays a word of `length` letters
+fn sim_player(
+    words: &[String],
+    length: usize,
+    options: &SimOptions,
+) -> Result<HangmanPlayer, HangmanError> {
+    let SimOptions {
+        strategy,
+        score_by,
+        position_bias,
+        ref frequencies,
+        seed,
+        ref openers,
+        bigram_threshold,
+        ref score_cache,
+        ..
+    } = *options;
+    let mut player = HangmanPlayer::new(words, length)?
+        .with_score_by(score_by)
+        .with_position_bias(position_bias);
+    if let Some(cache) = score_cache {
+        player = player.with_score_cache(cache.clone());
+    }
+    if let Some(openers) = openers
+        .as_ref()
+        .and_then(|openers| openers.get(&strategy)?.get(&length))
+    {
+        player = player.with_openers(openers.clone());
+    }
+    if let Some(frequencies) = frequencies {
+        player = player.with_frequencies(frequencies.clone());
+    }
+    if let Some(seed) = seed {
+        player = player.with_seed(seed);
+    }
+    if let Some(threshold) = bigram_threshold {
+        player = player.with_bigram_threshold(threshold);
+    }
+    Ok(player)
+}
+
+/// Best opening letters for each strategy and word length, best first
+pub type Openers = HashMap<Strategy, BTreeMap<usize, Vec<char>>>;
+
+/// Rank the top `n` opening letters for every word length in `words` under `options`, so they
+/// can be saved and passed to [`HangmanPlayer::with_openers`] instead of being scored each game.
+/// Ties are ordered alphabetically and the bigram threshold isn't applied, so the openers only
+/// match players without a seed or bigram threshold.
+pub fn compute_openers(
+    words: &[String],
+    n: usize,
+    options: &SimOptions,
+) -> Result<BTreeMap<usize, Vec<char>>, HangmanError> {
+    let lengths: BTreeSet<_> = words.iter().map(|word| word.chars().count()).collect();
+    lengths
+        .into_iter()
+        .map(|length| {
+            let mut player = HangmanPlayer::new(words, length)?
+                .with_score_by(options.score_by)
+                .with_position_bias(options.position_bias);
+            if let Some(frequencies) = &options.frequencies {
+                player = player.with_frequencies(frequencies.clone());
+            }
+            let mut letters = player.ranked_letters(options.strategy);
+            letters.truncate(n);
+            Ok((length, letters))
+        })
+        .collect()
+}
+
+/// Method used to choose which letter to guess next
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Strategy {
+    /// Guess the letter that appears in the most remaining words
+    #[default]
+    Frequency,
+
+    /// Guess the letter whose outcome splits the remaining words with the most entropy
+    Entropy,
+
+    /// Guess the letter that leaves the fewest remaining words in the worst case
+    Minimax,
+
+    /// Guess the letter most likely to be in the word, to make as few mistakes as possible
+    Safe,
+}
+
+/// Scores letters for [`HangmanPlayer::compute_letter_scores`], which drops letters that have
+/// already been guessed and sorts the rest from highest to lowest score
+pub trait ScoringStrategy: Send {
+    fn score(&self, player: &HangmanPlayer) -> Vec<(char, usize)>;
+}
+
+/// Score each letter by how many remaining words contain it, weighted by frequency and
+/// [`ScoreBy`]
+pub struct FrequencyScoring;
+
+impl ScoringStrategy for FrequencyScoring {
+    fn score(&self, player: &HangmanPlayer) -> Vec<(char, usize)> {
+        player
+            .letter_counts
+            .iter()
+            .map(|(&l, &count)| (l, count))
+            .collect()
+    }
+}
+
+/// Score each letter by how many different outcomes guessing it could have, counting every set
+/// of positions it could be revealed at plus it being absent. Letters that split the remaining
+/// words into more groups narrow them down faster on average.
+pub struct OutcomeScoring;
+
+impl ScoringStrategy for OutcomeScoring {
+    fn score(&self, player: &HangmanPlayer) -> Vec<(char, usize)> {
+        player
+            .alphabet
+            .iter()
+            .map(|&letter| {
+                let outcomes: HashSet<Vec<usize>> = player
+                    .available_words
+                    .iter()
+                    .map(|word| {
+                        word.chars()
+                            .enumerate()
+                            .filter_map(|(i, c)| (c == letter).then_some(i))
+                            .collect()
+                    })
+                    .collect();
+                (letter, outcomes.len())
+            })
+            .collect()
+    }
+}
+
+/// Score each letter by how many remaining words contain it, breaking ties by how many words its
+/// worst case outcome rules out, where an outcome is the set of positions it's revealed at. Among
+/// equally common letters, the one that splits the words into the most even groups wins.
+pub struct SplitScoring;
+
+impl ScoringStrategy for SplitScoring {
+    fn score(&self, player: &HangmanPlayer) -> Vec<(char, usize)> {
+        player
+            .alphabet
+            .iter()
+            .map(|&letter| {
+                let mut outcomes: HashMap<Vec<usize>, usize> = HashMap::new();
+                for word in player.available_words.iter() {
+                    let positions = word
+                        .chars()
+                        .enumerate()
+                        .filter_map(|(i, c)| (c == letter).then_some(i))
+                        .collect();
+                    *outcomes.entry(positions).or_default() += player.weight(word);
+                }
+                let total: usize = outcomes.values().sum();
+                let absent = outcomes.get(&vec![]).copied().unwrap_or(0);
+                let largest = outcomes.into_values().max().unwrap_or(0);
+                (
+                    letter,
+                    (total - absent)
+                        .saturating_mul(total + 1)
+                        .saturating_add(total - largest),
+                )
+            })
+            .collect()
+    }
+}
+
+/// What a letter scores for each remaining word when ranking by frequency
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum ScoreBy {
+    /// One point for each word containing the letter
+    #[default]
+    Presence,
+
+    /// One point for each blank the letter would fill, so letters that reveal more of the word
+    /// rank higher
+    Coverage,
+}
+
+impl ScoreBy {
+    /// Points each letter of `word` earns, with each position it's at weighted by `bias`
+    fn letter_points(self, word: &str, bias: PositionBias) -> Vec<(char, usize)> {
+        match (self, bias) {
+            (ScoreBy::Presence, PositionBias::None) => {
+                unique_letters(word).into_iter().map(|l| (l, 1)).collect()
+            }
+            (ScoreBy::Coverage, PositionBias::None) => {
+                let mut letters: Vec<_> = word.chars().collect();
+                letters.sort();
+                letters
+                    .chunk_by(|a, b| a == b)
+                    .map(|run| (run[0], run.len()))
+                    .collect()
+            }
+            _ => {
+                let len = word.chars().count();
+                let mut letters: Vec<_> = word
+                    .chars()
+                    .enumerate()
+                    .map(|(i, c)| (c, bias.weight(i, len)))
+                    .collect();
+                letters.sort();
+                letters
+                    .chunk_by(|(a, _), (b, _)| a == b)
+                    .map(|run| {
+                        let weights = run.iter().map(|&(_, weight)| weight);
+                        let points = match self {
+                            ScoreBy::Presence => weights.max().unwrap(),
+                            ScoreBy::Coverage => weights.sum(),
+                        };
+                        (run[0].0, points)
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Which end of the word the frequency strategy prefers to reveal first
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum PositionBias {
+    /// Every position counts the same
+    #[default]
+    None,
+
+    /// Letters count for more the nearer they are to the start of the word
+    Prefix,
+
+    /// Letters count for more the nearer they are to the end of the word
+    Suffix,
+}
+
+impl PositionBias {
+    /// Weight of a letter at zero-indexed `pos` in a word of `len` letters, from 1 at the
+    /// disfavored end up to `len` at the favored one
+    fn weight(self, pos: usize, len: usize) -> usize {
+        match self {
+            PositionBias::None => 1,
+            PositionBias::Prefix => len - pos,
+            PositionBias::Suffix => pos + 1,
+        }
+    }
+}
+
+/// Snapshot of the board taken before a guess
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryFrame {
+    pub guess: Vec<Option<char>>,
+    pub not_present: Vec<char>,
+    pub used_letters: Vec<char>,
+    /// Words that were still possible at this point, so undoing back to this frame doesn't need
+    /// to prune the whole word list again. Not saved with the game state, nor kept before the
+    /// first guess, when every word is possible.
+    #[serde(skip)]
+    pub available_words: Option<Vec<String>>,
+}
+
+/// Guesses made in a game, with the remaining words left out since they can be recomputed
+#[derive(Serialize, Deserialize)]
+pub struct GameState {
+    pub current_guess: Vec<Option<char>>,
+    pub not_present: Vec<char>,
+    pub used_letters: Vec<char>,
+    pub guess_history: Vec<HistoryFrame>,
+}
+
+/// Outcome of a simulated game
+#[derive(Clone, Serialize)]
+pub struct SimResults {
+    pub history: Vec<HistoryFrame>,
+    pub guesses: Vec<char>,
+    pub mistakes: usize,
+    pub won: bool,
+    /// Number of words still possible after each guess
+    pub remaining: Vec<usize>,
+    #[serde(skip)]
+    pub timings: Option<Timings>,
+}
+
+/// A guess made by [`guess_tree`], and what follows from each of its possible outcomes
+#[derive(Clone, Debug, Serialize)]
+pub struct GuessTree {
+    /// Number of words still possible before the guess
+    pub remaining: usize,
+    /// The answer, once it's the only word left
+    pub word: Option<String>,
+    /// Letter guessed next, or `None` if the game is over or the depth limit was reached
+    pub guess: Option<char>,
+    pub branches: Vec<GuessBranch>,
+}
+
+/// One possible outcome of a guess in a [`GuessTree`]
+#[derive(Clone, Debug, Serialize)]
+pub struct GuessBranch {
+    /// Positions the guessed letter turned up at, starting from 0; empty if it missed
+    pub positions: Vec<usize>,
+    pub tree: GuessTree,
+}
+
+/// Wall-clock time of every call to each phase of solving
+#[derive(Clone, Debug, Default)]
+pub struct Timings {
+    /// Calls to [`HangmanPlayer::compute_letter_scores`],
+    /// [`HangmanPlayer::compute_letter_entropies`] or [`HangmanPlayer::compute_letter_minimax`],
+    /// including the entropies [`HangmanPlayer::compute_letter_safety`] is worked out from.
+    /// Guesses found in a [`ScoreCache`] don't score any letters, so they aren't recorded.
+    pub scoring: Vec<Duration>,
+    /// Pruning the remaining words after each guess
+    pub pruning: Vec<Duration>,
+}
+
+impl SimResults {
+    /// How much each mistake counts towards [`SimResults::difficulty`], relative to one guess
+    pub const MISTAKE_WEIGHT: usize = 5;
+
+    /// Single score for how hard the word was to guess: the number of guesses taken, plus
+    /// [`SimResults::MISTAKE_WEIGHT`] for every mistake made
+    pub fn difficulty(&self) -> usize {
+        self.history.len() + self.mistakes * Self::MISTAKE_WEIGHT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn seeded_ties_are_the_same_every_call() {
+        let words = words(&["ab", "cd", "ef", "gh", "ij", "kl"]);
+        let player = HangmanPlayer::new(&words, 2).unwrap().with_seed(3);
+        for strategy in [Strategy::Frequency, Strategy::Entropy, Strategy::Safe] {
+            let first = player.best_guess(strategy);
+            for _ in 0..10 {
+                assert_eq!(player.best_guess(strategy), first);
+            }
+            let fresh = HangmanPlayer::new(&words, 2).unwrap().with_seed(3);
+            assert_eq!(fresh.best_guess(strategy), first);
+        }
+    }
+
+    #[test]
+    fn peek_matches_the_seeded_guess() {
+        let words = words(&["ab", "cd", "ef", "gh", "ij", "kl"]);
+        for seed in 0..8 {
+            let player = HangmanPlayer::new(&words, 2).unwrap().with_seed(seed);
+            let peeked = player.peek_best_guess(Strategy::Entropy);
+            assert_eq!(player.best_guess(Strategy::Entropy), peeked);
+        }
+    }
+
+    #[test]
+    fn undo_to_the_start_restores_every_word() {
+        let words = words(&["cat", "cot", "dog"]);
+        let mut player = HangmanPlayer::new(&words, 3).unwrap();
+        player.guess_letter('c', vec![0]).unwrap();
+        assert_eq!(player.available_words(), ["cat", "cot"]);
+        assert!(player.guess_history[0].available_words.is_none());
+        player.undo().unwrap();
+        assert_eq!(player.available_words(), words);
+        assert_eq!(player.current_guess(), [None, None, None]);
+    }
+
+    #[test]
+    fn accented_words_match_by_letter_count() {
+        let words = words(&["café", "crème", "table"]);
+        let player = HangmanPlayer::new(&words, 4).unwrap();
+        assert_eq!(player.available_words(), ["café"]);
+        assert_eq!(player.current_guess().len(), 4);
+        let mut player = HangmanPlayer::new(&words, 5).unwrap();
+        assert_eq!(player.available_words(), ["crème", "table"]);
+        player.guess_letter('è', vec![2]).unwrap();
+        assert_eq!(player.available_words(), ["crème"]);
+    }
+
+    #[test]
+    fn saved_game_resumes_with_the_same_suggestions() {
+        let words = words(&["cat", "cot", "cut", "dog", "dig", "bat"]);
+        let mut player = HangmanPlayer::new(&words, 3).unwrap();
+        player.guess_letter('t', vec![2]).unwrap();
+        player.guess_letter('e', vec![]).unwrap();
+        let saved = serde_json::to_string(&player.game_state()).unwrap();
+        let mut resumed =
+            HangmanPlayer::from_game_state(&words, serde_json::from_str(&saved).unwrap()).unwrap();
+        assert_eq!(resumed.available_words(), player.available_words());
+        assert_eq!(resumed.current_guess(), player.current_guess());
+        assert_eq!(
+            resumed.compute_letter_scores(),
+            player.compute_letter_scores()
+        );
+        resumed.undo().unwrap();
+        player.undo().unwrap();
+        assert_eq!(resumed.available_words(), player.available_words());
+    }
+
+    #[test]
+    fn cyrillic_words_only_suggest_cyrillic_letters() {
+        let words = words(&["кот", "кит", "дом", "сок"]);
+        let player = HangmanPlayer::new(&words, 3).unwrap();
+        let cyrillic = |letter: &char| ('а'..='я').contains(letter) || *letter == 'ё';
+        assert!(player.alphabet().iter().all(cyrillic));
+        let scores = player.compute_letter_scores();
+        assert!(!scores.is_empty());
+        assert!(scores.iter().all(|(letter, _)| cyrillic(letter)));
+        assert_eq!(scores[..2], [('к', 3), ('о', 3)]);
+    }
+
+    #[test]
+    fn contradicting_guesses_are_rejected_without_changes() {
+        let words = words(&["cat", "cot", "dog", "bat"]);
+        let mut player = HangmanPlayer::new(&words, 3).unwrap();
+        player.guess_letter('t', vec![2]).unwrap();
+        player.guess_letter('b', vec![]).unwrap();
+        let available = player.available_words().to_vec();
+        let history = player.history().len();
+        let board = player.current_guess().to_vec();
+        for (letter, positions) in [
+            ('t', vec![]),
+            ('b', vec![0]),
+            ('c', vec![2]),
+            ('t', vec![0]),
+        ] {
+            assert!(matches!(
+                player.guess_letter(letter, positions),
+                Err(HangmanError::Contradiction(message)) if message.contains("contradicts")
+            ));
+        }
+        assert_eq!(player.available_words(), available);
+        assert_eq!(player.history().len(), history);
+        assert_eq!(player.current_guess(), board);
+        assert_eq!(player.not_present(), ['b']);
+    }
+
+    #[test]
+    fn missing_length_lists_the_lengths_available() {
+        let words = words(&["cat", "bird", "horse", "eagle"]);
+        let error = HangmanPlayer::new(&words, 2).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "No 2-letter words in dictionary (lengths available: 3, 4, 5)"
+        );
+        let error = HangmanPlayer::new(&words, 9).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "No 9-letter words in dictionary, the longest words have 5 letters"
+        );
+    }
+
+    #[test]
+    fn precomputed_openers_match_a_fresh_first_guess() {
+        let words = words(&[
+            "cat", "cot", "dog", "bird", "bard", "horse", "house", "mouse",
+        ]);
+        for strategy in [Strategy::Frequency, Strategy::Entropy, Strategy::Safe] {
+            let options = SimOptions {
+                strategy,
+                ..SimOptions::default()
+            };
+            let openers = compute_openers(&words, 3, &options).unwrap();
+            assert_eq!(openers.keys().copied().collect::<Vec<_>>(), [3, 4, 5]);
+            for (&length, letters) in &openers {
+                let fresh = HangmanPlayer::new(&words, length).unwrap();
+                assert_eq!(letters[..], fresh.ranked_letters(strategy)[..letters.len()]);
+                let cached = fresh.with_openers(letters.clone());
+                assert_eq!(
+                    cached.best_guess(strategy),
+                    HangmanPlayer::new(&words, length)
+                        .unwrap()
+                        .best_guess(strategy)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn words_of_other_lengths_are_pruned() {
+        let mut player = HangmanPlayer::new(&words(&["cat", "cot"]), 3).unwrap();
+        player.available_words = words(&["cat", "ca", "cats", "cot", "c"]);
+        player.recount_letters();
+        player.guess_letter('c', vec![0]).unwrap();
+        assert_eq!(player.available_words(), ["cat", "cot"]);
+
+        let mixed = words(&["ca", "cat", "cats", "scat"]);
+        assert_eq!(search(&mixed, &[Some('c'), None, None], &[], &[]), ["cat"]);
+    }
+
+    #[test]
+    fn revealed_letters_are_ruled_out_of_other_blanks() {
+        let words = words(&["banana", "bananb", "cabana", "bazanb"]);
+        let mut player = HangmanPlayer::new(&words, 6).unwrap();
+        player.guess_letter('a', vec![1, 3]).unwrap();
+        assert_eq!(player.available_words(), ["bananb", "bazanb"]);
+        assert_eq!(
+            player.current_guess(),
+            [Some('b'), Some('a'), None, Some('a'), Some('n'), Some('b')]
+        );
+        assert_eq!(
+            player.not_at(),
+            [vec![], vec![], vec!['a'], vec![], vec![], vec![]]
+        );
+        player.guess_letter('n', vec![4]).unwrap();
+        assert_eq!(player.available_words(), ["bazanb"]);
+    }
+
+    #[test]
+    fn undo_and_redo_restore_the_remaining_words() {
+        let words = words(&["cat", "cot", "cut", "dog", "dig", "bat"]);
+        let mut player = HangmanPlayer::new(&words, 3).unwrap();
+        player.guess_letter('t', vec![2]).unwrap();
+        let after_first = player.available_words().to_vec();
+        player.guess_letter('c', vec![0]).unwrap();
+        let after_second = player.available_words().to_vec();
+        assert_ne!(after_first, after_second);
+
+        let undone = player.undo().unwrap();
+        assert_eq!(player.available_words(), after_first);
+        assert_eq!(player.current_guess(), [None, None, Some('t')]);
+        let mut fresh = HangmanPlayer::new(&words, 3).unwrap();
+        fresh.guess_letter('t', vec![2]).unwrap();
+        assert_eq!(
+            player.compute_letter_scores(),
+            fresh.compute_letter_scores()
+        );
+        player.redo(undone);
+        assert_eq!(player.available_words(), after_second);
+        assert_eq!(player.used_letters(), ['t', 'c']);
+    }
+
+    #[test]
+    fn finding_the_word_on_the_last_allowed_miss_loses() {
+        let words = words(&["cat", "cot", "dog"]);
+        let options = SimOptions {
+            max_mistakes: Some(1),
+            ..SimOptions::default()
+        };
+        // c misses, which leaves only dog, but that miss was the last one allowed
+        let results = simulate(&words, "dog", &options).unwrap();
+        assert_eq!(results.guesses, ['c']);
+        assert_eq!(results.mistakes, 1);
+        assert!(!results.won);
+
+        let mut tree = None;
+        simulate_tree(&words, 3, &["dog"], &options, &mut |_, result| {
+            tree = Some(result.unwrap())
+        });
+        let tree = tree.unwrap();
+        assert_eq!((tree.mistakes, tree.won), (1, false));
+    }
+
+    #[test]
+    fn simulating_as_a_tree_matches_simulating_each_word() {
+        let words = words(&[
+            "bird", "bard", "bold", "bolt", "cold", "colt", "card", "cord", "lord", "word", "ward",
+            "warm", "worm", "form", "farm", "harm", "cat", "cot",
+        ]);
+        let targets: Vec<_> = words.iter().filter(|word| word.len() == 4).collect();
+        for &strategy in Strategy::value_variants() {
+            for seed in [None, Some(7)] {
+                for bigram_threshold in [None, Some(4)] {
+                    for max_mistakes in [None, Some(2)] {
+                        let options = SimOptions {
+                            strategy,
+                            seed,
+                            bigram_threshold,
+                            max_mistakes,
+                            ..SimOptions::default()
+                        };
+                        let targets: Vec<&str> = targets.iter().map(|word| word.as_str()).collect();
+                        let mut tree = HashMap::new();
+                        simulate_tree(&words, 4, &targets, &options, &mut |word, result| {
+                            tree.insert(word.to_string(), result.unwrap());
+                        });
+                        assert_eq!(tree.len(), targets.len());
+                        for word in targets {
+                            let single = simulate(&words, word, &options).unwrap();
+                            assert_eq!(
+                                serde_json::to_string(&tree[word]).unwrap(),
+                                serde_json::to_string(&single).unwrap(),
+                                "{word} with {}, seed {seed:?}, bigram threshold \
+                                 {bigram_threshold:?}, max mistakes {max_mistakes:?}",
+                                strategy.to_possible_value().unwrap().get_name()
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rejected_words_stay_out_until_reset() {
+        let words = words(&["cat", "cot", "dog"]);
+        let mut player = HangmanPlayer::new(&words, 3).unwrap();
+        player.guess_letter('t', vec![2]).unwrap();
+        player.reject_word("cat");
+        assert_eq!(player.available_words(), ["cot"]);
+        player.undo().unwrap();
+        assert_eq!(player.available_words(), ["cot", "dog"]);
+        player.reset();
+        assert_eq!(player.available_words(), words);
+    }
+
+    #[test]
+    fn known_letters_only_narrow_their_own_positions() {
+        let words = words(&["paper", "plant", "piano", "quilt"]);
+        let mut player = HangmanPlayer::new(&words, 5).unwrap();
+        player
+            .fill_known_letters(&[Some('p'), None, None, None, None])
+            .unwrap();
+        assert_eq!(player.available_words(), ["paper", "plant", "piano"]);
+        assert!(player.used_letters().is_empty());
+        player.guess_letter('a', vec![2]).unwrap();
+        player.undo().unwrap();
+        assert_eq!(player.current_guess()[0], Some('p'));
+        assert_eq!(player.available_words(), ["paper", "plant", "piano"]);
+    }
+
+    /// Common english words of four to six letters, to compare strategies over
+    const ENGLISH: &str = "
+        able about above added after again almost also always answer april area areas around audio
+        away back based beach become been before being below best better black board book books
+        both bridge budget call came camera cards case castle center change check child china
+        choice church circle city class click close color come common cotton could course court
+        daily damage david desert design dinner doctor door down drive during each editor email
+        energy engine enter entire even event every face fact family father feel field figure
+        files find finger first five flower forest forum found four free friend from full future
+        game games garden girl girls give given going gone good great green ground group growth
+        guide half hand have head health hear help here high hold home hope hotel hours house
+        human hunter idea image income index island issue items just keep kind kitten know ladder
+        land large last late learn least left legal less letter level life light like line links
+        little live local login long look lost love made major make manner many marble march
+        market means media middle might mind minute model moment money month months more most
+        mother move movie much music must name nation nature near need needle needs never next
+        night north note number offer office only open orange order other over pages paid paper
+        parent part party pencil people pepper period person phone photo pillow place plan planet
+        play point poker policy posts power press price print prison profit public quote rabbit
+        radio range rates read real really record region reply report result return right road
+        room rule saddle safety said sales same school season seem series shall share short should
+        show side silver simple since sites skills small social some source south space spirit
+        spring staff start state still stock store story street strong study such summer supply
+        sure system table take talent tell terms texas than that their them theory there these
+        they things think this those three time times title today tools topic total toward town
+        track trade travel tree true tunnel turn under united until used users using value very
+        video violin visit visual walk wall wallet want water ways week well went were what when
+        where which while white wide wife will wind window winter wish with women wonder wood word
+        work worker world would write year years yellow young your
+    ";
+
+    /// Mean mistakes and guesses taken to find every word in `ENGLISH`
+    fn mean_mistakes_and_guesses(options: &SimOptions) -> (f64, f64) {
+        let words: Vec<String> = ENGLISH.split_whitespace().map(String::from).collect();
+        let results: Vec<_> = words
+            .iter()
+            .map(|word| simulate(&words, word, options).unwrap())
+            .collect();
+        let mean = |total: usize| total as f64 / results.len() as f64;
+        (
+            mean(results.iter().map(|r| r.mistakes).sum()),
+            mean(results.iter().map(|r| r.guesses.len()).sum()),
+        )
+    }
+
+    #[test]
+    fn entropy_takes_fewer_guesses_than_frequency() {
+        let (frequency_mistakes, frequency_guesses) =
+            mean_mistakes_and_guesses(&SimOptions::default());
+        let (entropy_mistakes, entropy_guesses) = mean_mistakes_and_guesses(&SimOptions {
+            strategy: Strategy::Entropy,
+            ..SimOptions::default()
+        });
+        assert!(
+            entropy_guesses < frequency_guesses,
+            "entropy {entropy_guesses}, frequency {frequency_guesses}"
+        );
+        // splitting the words evenly means guessing letters that are often missing
+        assert!(
+            entropy_mistakes > frequency_mistakes,
+            "entropy {entropy_mistakes}, frequency {frequency_mistakes}"
+        );
+    }
+
+    #[test]
+    fn bigram_threshold_makes_fewer_mistakes() {
+        let (plain_mistakes, _) = mean_mistakes_and_guesses(&SimOptions::default());
+        let (bigram_mistakes, _) = mean_mistakes_and_guesses(&SimOptions {
+            bigram_threshold: Some(8),
+            ..SimOptions::default()
+        });
+        assert!(
+            bigram_mistakes < plain_mistakes,
+            "with bigrams {bigram_mistakes}, without {plain_mistakes}"
+        );
+    }
+
+    #[test]
+    fn safe_makes_no_more_mistakes_than_frequency() {
+        let (frequency_mistakes, _) = mean_mistakes_and_guesses(&SimOptions::default());
+        let (safe_mistakes, _) = mean_mistakes_and_guesses(&SimOptions {
+            strategy: Strategy::Safe,
+            ..SimOptions::default()
+        });
+        assert!(
+            safe_mistakes <= frequency_mistakes,
+            "safe {safe_mistakes}, frequency {frequency_mistakes}"
+        );
+    }
+}
@@ -1,436 +1,1739 @@
-#![feature(iterator_try_collect)]
-#![feature(file_create_new)]
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     error::Error,
+    fmt::Display,
     fs::File,
-    io::{stdin, stdout, BufRead, BufReader, BufWriter, Write},
+    io::{self, stdin, stdout, BufRead, BufReader, BufWriter, ErrorKind, IsTerminal, Write},
     num::ParseIntError,
     ops::ControlFlow,
-    path::PathBuf,
-    sync::mpsc::channel,
+    path::{Path, PathBuf},
+    process,
+    sync::{
+        atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering},
+        mpsc::channel,
+        Arc, Mutex,
+    },
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
-use clap::{ArgAction, Parser, Subcommand};
-use progress_observer::{reprint, Observer};
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
+use hangman::{
+    alphabet, analyze, compute_openers, guess_tree, is_lost, load_frequencies, load_words,
+    load_words_glob, lowercase_words, miss_guesses, search, simulate, simulate_tree, top_words,
+    word_weight, DownloadOptions, Frequencies, GameState, GuessTree, HangmanPlayer, HistoryFrame,
+    LengthStats, Openers, PositionBias, ScoreBy, ScoreCache, SimOptions, SimResults, Strategy,
+    Timings,
+};
+use progress_observer::Observer;
+use rand::{rngs::StdRng, seq::IndexedRandom, SeedableRng};
+use rayon::{prelude::*, ThreadPoolBuilder};
 use regex::Regex;
 use serde::Serialize;
-use threadpool::ThreadPool;
 use ControlFlow::*;
 
 type Err = Box<dyn Error>;
 
-fn load_words(args: &Args) -> Result<Vec<String>, Err> {
-    if let Ok(words_file) = File::open(&args.words_file) {
-        println!("Loading from {:?}", &args.words_file);
-        Ok(BufReader::new(words_file).lines().try_collect()?)
-    } else {
-        println!(
-            "Downloading words from {} and saving to {:?}",
-            &args.word_source, &args.words_file
-        );
-        let mut words_file = BufWriter::new(File::create_new(&args.words_file)?);
-        Ok(BufReader::new(reqwest::blocking::get(&args.word_source)?)
-            .lines()
-            .map(|line| {
-                let line = line?;
-                words_file.write(line.as_bytes())?;
-                words_file.write(b"\n")?;
-                Ok::<_, Err>(line)
-            })
-            .try_collect()?)
-    }
-}
+/// How much to report on stderr: 0 when quiet, 1 by default, and higher with each `-v`
+static VERBOSITY: AtomicU8 = AtomicU8::new(1);
 
-struct HangmanPlayer {
-    available_words: Vec<String>,
-    current_guess: Vec<Option<char>>,
-    not_present: Vec<char>,
-    used_letters: Vec<char>,
-    guess_history: Vec<HistoryFrame>,
-}
+/// Set by the first Ctrl-C during `play`, after which the next line read is where to save the game
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
 
-impl std::fmt::Debug for HangmanPlayer {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("HangmanPlayer")
-            .field("available_words.len()", &self.available_words.len())
-            .field("current_guess", &self.current_guess)
-            .field("not_present", &self.not_present)
-            // .field("guess_history", &self.guess_history)
-            .finish()
-    }
-}
+/// The game being played, as of the last guess, so it can be saved when interrupted
+static IN_PROGRESS: Mutex<Option<GameState>> = Mutex::new(None);
 
-impl HangmanPlayer {
-    pub fn new(words: Vec<String>, word_length: usize) -> Result<HangmanPlayer, Err> {
-        let words: Vec<String> = words
-            .into_iter()
-            .filter(|word| word.len() == word_length)
-            .collect();
-        Ok(HangmanPlayer {
-            available_words: words.clone(),
-            current_guess: vec![None; word_length],
-            not_present: vec![],
-            used_letters: vec![],
-            guess_history: vec![],
-        })
-    }
+/// Print a message to stderr if the verbosity is at least `level`
+macro_rules! log {
+    ($level:expr, $($arg:tt)*) => {
+        if VERBOSITY.load(Ordering::Relaxed) >= $level {
+            eprintln!($($arg)*);
+        }
+    };
+}
 
-    fn compute_letter_scores(&self) -> Vec<(char, usize)> {
-        let mut counts: HashMap<_, _> = ('a'..='z')
-            .filter(|l| !self.used_letters.contains(&l))
-            .map(|l| (l, 0usize))
-            .collect();
-        for word in self.available_words.iter() {
-            let mut unique_letters: Vec<_> = word.chars().collect();
-            unique_letters.sort();
-            unique_letters.dedup();
-            for letter in unique_letters {
-                if let Entry::Occupied(mut entry) = counts.entry(letter) {
-                    *entry.get_mut() += 1;
-                }
-            }
+/// Overwrite the current line of stderr with a progress update, unless quiet
+macro_rules! progress {
+    ($($arg:tt)*) => {
+        if VERBOSITY.load(Ordering::Relaxed) >= 1 {
+            eprint!("\r\x1b[2K{}", format!($($arg)*));
         }
-        let mut sorted_counts: Vec<_> = counts.into_iter().collect();
-        sorted_counts.sort_by(|(_, a), (_, b)| b.cmp(a));
+    };
+}
 
-        return sorted_counts;
-    }
+/// Overwrite the current line of stdout, for redrawing something in place
+macro_rules! reprint {
+    ($($arg:tt)*) => {
+        print!("\r\x1b[2K{}", format!($($arg)*))
+    };
+}
 
-    fn push_history(&mut self) {
-        self.guess_history.push(HistoryFrame {
-            guess: self.current_guess.clone(),
-            not_present: self.not_present.clone(),
-        });
-    }
+mod serve;
+mod tui;
+
+struct PlayerUI<'a> {
+    words: &'a [String],
+    config: PlayerConfig,
+    player: HangmanPlayer,
+    args: PlayArgs,
+    guess_pattern: Regex,
+    redo_stack: Vec<HistoryFrame>,
+    /// Score of each letter the last time suggestions were shown, to show how they've changed
+    previous_scores: HashMap<char, f64>,
+    /// Every letter's score this turn, best first, for the `scores` command
+    all_scores: Vec<(char, String)>,
+    /// The answer, when the solver is playing by itself with `--auto`
+    hidden: Option<String>,
+    color: bool,
+}
 
-    fn mark_result(&mut self, letter: char, positions: Vec<usize>) {
-        self.push_history();
+/// Shown when a guess doesn't fit `<letter> [positions]`, along with how to type one
+const INVALID_GUESS: &str = "Invalid guess format";
 
-        self.used_letters.push(letter);
-        if positions.is_empty() {
-            self.not_present.push(letter);
+// help shown when an empty line is entered
+const GUESS_HELPTEXT: &str = "Type your guess in the following format: <letter> [positions]
+example 1: the letter n appears at the start of the word: type `n 1`
+example 2: the letter e appears as the second and fourth letter: type `e 2 4` or `e 2,4`
+example 3: the letter s appears as the first three letters: type `s 1-3`
+example 4: the letter g does not appear in the word: type `g`";
+const BOARD_HELPTEXT: &str =
+    "Type the board as it is now followed by the wrong letters: <board> [wrong letters]
+example: the word looks like _e__e and a, s and t were wrong: type `_e__e ast`";
+const COMMAND_HELPTEXT: &str = "Type `undo` to undo the last input, and `redo` to reapply it
+Type `words` to list every word that is still possible
+Type `scores` to list the score of every letter, not just the top guesses
+Type `reset` to clear every guess and start this word over, with any ruled out words back in
+Type `reveal` to give up and take the most likely word as the answer
+Type `new <length>` to start over guessing a word of a different length
+Type `save <path>` to save the game so it can be resumed later with `--resume <path>`";
+
+// ansi color codes for the board and suggestions
+const GREEN: &str = "32";
+const GRAY: &str = "90";
+const RED: &str = "31";
+const HIGHLIGHT: &str = "1;33";
+
+impl<'a> PlayerUI<'a> {
+    /// Start a game from a saved file, the length given on the command line, or a length asked
+    /// for interactively if neither was given
+    pub fn new(
+        words: &'a [String],
+        args: PlayArgs,
+        config: PlayerConfig,
+    ) -> Result<PlayerUI<'a>, Err> {
+        let hidden = if args.auto {
+            Some(read_hidden_word()?)
         } else {
-            for pos in positions {
-                self.current_guess[pos] = Some(letter);
+            None
+        };
+        let known = args.known.as_deref().map(parse_pattern);
+        let letters = match (args.letters, &known) {
+            (Some(letters), Some(known)) if letters != known.len() => Err(format!(
+                "The known letters pattern has {} letters, but the word has {letters}",
+                known.len()
+            ))?,
+            (None, Some(known)) => Some(known.len()),
+            (letters, _) => letters.or(hidden.as_ref().map(|word| word.chars().count())),
+        };
+        let mut player = match (&args.resume, letters) {
+            (Some(path), _) => {
+                let state: GameState = serde_json::from_reader(BufReader::new(File::open(path)?))?;
+                HangmanPlayer::from_game_state(words, state)?
             }
-        }
-    }
-
-    fn prune_words(&mut self) -> Vec<Vec<char>> {
-        let mut potential_letters = vec![vec![]; self.current_guess.len()];
-
-        self.available_words.retain(|word| {
-            let mut potential_additions = vec![vec![]; self.current_guess.len()];
-            for (
-                (potential_place_additions, potential_place_letters),
-                (word_letter, guess_letter),
-            ) in (potential_additions.iter_mut().zip(potential_letters.iter()))
-                .zip(word.chars().zip(self.current_guess.iter()))
-            {
-                if self.not_present.contains(&word_letter) {
-                    return false;
+            (None, Some(letters)) => HangmanPlayer::new(words, letters)?,
+            (None, None) if args.any_length => narrow_length(words, &args, &config)?,
+            (None, None) => loop {
+                let length = read_length()?;
+                match HangmanPlayer::new(words, length) {
+                    Ok(player) => break player,
+                    Err(e) => println!("{e}"),
                 }
-                match guess_letter {
-                    Some(placed_letter) => {
-                        if placed_letter != &word_letter {
-                            return false;
-                        }
-                    }
-                    None if potential_place_letters.len() < 26 => {
-                        potential_place_additions.push(word_letter)
-                    }
-                    _ => {}
+            },
+        };
+        if let Some(mut known) = known {
+            // fold the pattern the same way typed guesses are, unless the dictionary is mixed case
+            if !player.alphabet().iter().any(|c| c.is_uppercase()) {
+                for letter in known.iter_mut().flatten() {
+                    *letter = letter.to_lowercase().next().unwrap_or(*letter);
                 }
             }
-            for (potential_place_additions, potential_place_letters) in potential_additions
-                .into_iter()
-                .zip(potential_letters.iter_mut())
-            {
-                for letter_addition in potential_place_additions {
-                    if !potential_place_letters.contains(&letter_addition) {
-                        potential_place_letters.push(letter_addition);
-                    }
-                }
+            player.fill_known_letters(&known)?;
+            if player.available_words().is_empty() {
+                Err(format!(
+                    "No {}-letter word matches the known letters {}",
+                    known.len(),
+                    args.known.as_deref().unwrap_or_default()
+                ))?;
             }
-            true
-        });
-        potential_letters
-    }
-
-    fn fill_certain_letters(&mut self, potential_letters: Vec<Vec<char>>) {
-        for (guess_letter, potential_letter) in self.current_guess.iter_mut().zip(potential_letters)
-        {
-            if let (None, &[letter]) = (&guess_letter, &potential_letter[..]) {
-                *guess_letter = Some(letter);
+        }
+        let mut player = config.apply(player, args.strategy);
+        if args.timing {
+            player = player.with_timing();
+        }
+        if let Some(word) = &hidden {
+            if word.chars().count() != player.current_guess().len() {
+                Err(format!(
+                    "{word} doesn't have {} letters",
+                    player.current_guess().len()
+                ))?;
             }
         }
+        if args.dictionary_stats {
+            log!(
+                1,
+                "{} {}-letter words loaded, {} distinct letters, most common opener: {}",
+                player.available_words().len(),
+                player.current_guess().len(),
+                player
+                    .letter_counts()
+                    .values()
+                    .filter(|&&count| count > 0)
+                    .count(),
+                player
+                    .best_guess(args.strategy)
+                    .map_or("-".to_string(), String::from)
+            );
+        }
+        Ok(PlayerUI {
+            hidden,
+            color: match args.color {
+                ColorChoice::Auto => stdout().is_terminal(),
+                ColorChoice::Always => true,
+                ColorChoice::Never => false,
+            },
+            guess_pattern: guess_pattern(&player),
+            words,
+            config,
+            player,
+            args,
+            redo_stack: vec![],
+            previous_scores: HashMap::new(),
+            all_scores: vec![],
+        })
     }
 
-    fn prune_and_fill_certain_letters(&mut self) {
-        let potential_letters = self.prune_words();
-        self.fill_certain_letters(potential_letters);
+    /// Throw away the current game and start guessing a word of a different length
+    fn restart(&mut self, length: usize) -> Result<(), Err> {
+        let mut player = self
+            .config
+            .apply(HangmanPlayer::new(self.words, length)?, self.args.strategy);
+        if self.args.timing {
+            player = player.with_timing();
+        }
+        self.guess_pattern = guess_pattern(&player);
+        self.player = player;
+        self.redo_stack.clear();
+        self.previous_scores.clear();
+        Ok(())
     }
-}
 
-struct PlayerUI {
-    player: HangmanPlayer,
-    args: PlayArgs,
-    guess_pattern: Regex,
-    original_word_list: Vec<String>,
-}
+    /// Wrap `text` in the ansi escape `code` if color is enabled
+    fn paint(&self, code: &str, text: impl Display) -> String {
+        if self.color {
+            format!("\x1b[{code}m{text}\x1b[0m")
+        } else {
+            text.to_string()
+        }
+    }
 
-impl PlayerUI {
-    pub fn new(player: HangmanPlayer, args: PlayArgs) -> PlayerUI {
-        PlayerUI {
-            original_word_list: player.available_words.clone(),
-            player,
-            args,
-            guess_pattern: Regex::new(r"^([a-z])(( [0-9]+)*)$").unwrap(),
+    /// The letter most likely to fill each blank, and the share of remaining words that have it
+    /// there, like `likely letters: e(40%) · s(25%)`
+    fn likely_letters(&self) -> Option<String> {
+        let words = self.player.available_words();
+        if words.is_empty() {
+            return None;
         }
+        let word_chars: Vec<Vec<char>> = words.iter().map(|word| word.chars().collect()).collect();
+        let slots: Vec<_> = self
+            .player
+            .current_guess()
+            .iter()
+            .enumerate()
+            .filter(|(_, letter)| letter.is_none())
+            .map(|(i, _)| {
+                let mut counts: BTreeMap<char, usize> = BTreeMap::new();
+                for chars in &word_chars {
+                    *counts.entry(chars[i]).or_default() += 1;
+                }
+                let (letter, count) = counts
+                    .into_iter()
+                    .max_by_key(|&(letter, count)| (count, Reverse(letter)))
+                    .unwrap();
+                format!(
+                    "{letter}({:.0}%)",
+                    count as f64 / words.len() as f64 * 100.0
+                )
+            })
+            .collect();
+        (!slots.is_empty()).then(|| format!("likely letters: {}", slots.join(" · ")))
     }
 
     fn print_stats(&self) {
         println!(
             "current guess: {}",
             self.player
-                .current_guess
+                .current_guess()
                 .iter()
                 .map(|letter| match letter {
-                    None => "_".to_string(),
-                    Some(letter) => (*letter).into(),
+                    None => self.paint(GRAY, "_"),
+                    Some(letter) => self.paint(GREEN, letter),
                 })
                 .collect::<Vec<_>>()
                 .join(" ")
         );
-        if !self.player.not_present.is_empty() {
+        if self.args.show_positions {
+            if let Some(likely) = self.likely_letters() {
+                println!("{likely}");
+            }
+        }
+        if !self.player.not_present().is_empty() {
             println!(
                 "letters not present: {}",
                 self.player
-                    .not_present
+                    .not_present()
                     .iter()
-                    .cloned()
-                    .map(String::from)
+                    .map(|letter| self.paint(RED, letter))
                     .collect::<Vec<_>>()
                     .join(" ")
             )
         }
-        println!("{} possible words", self.player.available_words.len());
+        let (plausible, dead): (Vec<&char>, Vec<&char>) = self
+            .player
+            .alphabet()
+            .iter()
+            .filter(|l| !self.player.used_letters().contains(l))
+            .partition(|l| self.player.letter_counts().get(l).is_some_and(|&n| n > 0));
+        let plausible = plausible.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+        let dead = dead.iter().map(|l| self.paint(GRAY, l)).collect::<Vec<_>>();
+        match (plausible.is_empty(), dead.is_empty()) {
+            (true, true) => {}
+            (false, true) => println!("letters left: {}", plausible.join(" ")),
+            (true, false) => println!("letters left: none (dead: {})", dead.join(" ")),
+            (false, false) => println!(
+                "letters left: {} (dead: {})",
+                plausible.join(" "),
+                dead.join(" ")
+            ),
+        }
+        println!("{} possible words", self.player.available_words().len());
+        println!(
+            "approx {:.1} guesses remaining",
+            self.player.estimated_guesses_remaining()
+        );
+        if let Some(word) = self.player.most_likely_word() {
+            println!("most likely: {word}");
+        }
+        if let Some(max_mistakes) = self.args.max_mistakes {
+            let mistakes = self.player.not_present().len();
+            println!("{}", gallows(mistakes, max_mistakes));
+            println!("mistakes: {mistakes}/{max_mistakes}");
+        }
     }
 
-    fn show_scores_guesses_possibilities(&self, letter_scores: &Vec<(char, usize)>) {
-        if self.player.available_words.len() <= self.args.display_guesses_threshold {
+    fn show_scores_guesses_possibilities<S: Score>(
+        &mut self,
+        letter_scores: &[(char, S)],
+    ) -> Result<(), Err> {
+        if self.args.json_suggestions {
+            let turn = TurnSuggestions {
+                current_guess: self.player.current_guess(),
+                not_present: self.player.not_present(),
+                remaining: self.player.available_words().len(),
+                suggestions: &letter_scores[..self.args.num_suggestions.min(letter_scores.len())],
+            };
+            eprintln!("{}", serde_json::to_string(&turn)?);
+            return Ok(());
+        }
+        self.all_scores = letter_scores
+            .iter()
+            .map(|(letter, score)| (*letter, score.to_string()))
+            .collect();
+        let previous_scores = std::mem::replace(
+            &mut self.previous_scores,
+            letter_scores
+                .iter()
+                .filter_map(|(letter, score)| Some((*letter, score.value()?)))
+                .collect(),
+        );
+
+        if self.player.available_words().len() <= self.args.display_guesses_threshold {
             println!("Possibilities:");
 
-            for word in self.player.available_words.iter() {
+            for word in self.player.words_by_likelihood() {
                 println!("{word}");
             }
         }
 
+        let words = self.player.available_words();
         println!("Top {} guesses:", self.args.num_suggestions);
         for (i, (letter, score)) in letter_scores
-            .into_iter()
+            .iter()
             .take(self.args.num_suggestions)
             .enumerate()
         {
-            println!("{}. {letter}: {score}", i + 1);
+            let containing = words.iter().filter(|w| w.contains(*letter)).count();
+            let delta = match (score.value(), previous_scores.get(letter)) {
+                (Some(score), Some(previous)) if score.fract() == 0.0 => {
+                    format!(" ({:+})", score - previous)
+                }
+                (Some(score), Some(previous)) => format!(" ({:+.3})", score - previous),
+                _ => String::new(),
+            };
+            let line = format!(
+                "{}. {letter}: {score}{delta} ({:.1}%)",
+                i + 1,
+                containing as f64 / words.len() as f64 * 100.0
+            );
+            if i == 0 {
+                println!("{}", self.paint(HIGHLIGHT, line));
+            } else {
+                println!("{line}");
+            }
         }
+        Ok(())
     }
 
-    fn read_guess(&self, used: &[char]) -> Result<ControlFlow<(char, Vec<usize>), Undo>, Err> {
-        const HELPTEXT: &str = "Type your guess in the following format: <letter> [positions]
-example 1: the letter n appears at the start of the word: type `n 1`
-example 2: the letter e appears as the second and fourth letter: type `e 2 4`
-example 3: the letter g does not appear in the word: type `g`
-Type `undo` to undo the last input";
+    /// Print every letter's score this turn, not just the top suggestions
+    fn show_all_scores(&self) {
+        if self.all_scores.is_empty() {
+            println!("No letters to score this turn");
+        }
+        for line in score_table(&self.all_scores, 80) {
+            println!("{line}");
+        }
+    }
+
+    fn show_words(&self) -> Result<(), Err> {
+        const PAGE_SIZE: usize = 50;
+        let words = self.player.available_words();
+        for (i, page) in words.chunks(PAGE_SIZE).enumerate() {
+            for word in page {
+                println!("{word}");
+            }
+            if (i + 1) * PAGE_SIZE < words.len() {
+                print!(
+                    "-- {}/{} words shown, hit enter for more or type q to stop -- ",
+                    (i + 1) * PAGE_SIZE,
+                    words.len()
+                );
+                stdout().flush()?;
+                let mut response = String::new();
+                if read_line(&mut response)? == 0 || response.trim().eq_ignore_ascii_case("q") {
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn save(&self, path: &Path) -> Result<(), Err> {
+        let file = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(file, &self.player.game_state())?;
+        Ok(())
+    }
+
+    /// The most likely word to give up with, and if other words are still possible, a line
+    /// listing the most likely of them to show before asking whether to give up
+    fn reveal(&self) -> Option<(String, Option<String>)> {
+        let words = self.player.words_by_likelihood();
+        match words[..] {
+            [] => None,
+            [word] => Some((word.to_string(), None)),
+            [word, ..] => Some((
+                word.to_string(),
+                Some(format!(
+                    "{} words are still possible, most likely first: {}",
+                    words.len(),
+                    words[..self.args.num_suggestions.min(words.len())].join(", ")
+                )),
+            )),
+        }
+    }
+
+    fn read_guess(&self) -> Result<ControlFlow<Guesses, GameAction>, Err> {
+        let (prompt, helptext) = match self.args.input_mode {
+            InputMode::Incremental => (
+                "Type the letter you guessed, and if/where it appears in the word",
+                GUESS_HELPTEXT,
+            ),
+            InputMode::Board => (
+                "Type the board as it is now, and any wrong letters",
+                BOARD_HELPTEXT,
+            ),
+        };
         loop {
-            print!("Type the letter you guessed, and if/where it appears in the word (hit enter for help): ");
+            print!("{prompt} (hit enter for help): ");
             stdout().flush()?;
-            let mut guess_raw = String::new();
-            stdin().read_line(&mut guess_raw)?;
-            guess_raw = guess_raw.trim().to_lowercase().to_string();
+            let mut raw = String::new();
+            if read_line(&mut raw)? == 0 {
+                println!();
+                Err("Input ended before the game was over")?;
+            }
+
+            if let Some(path) = raw.trim().strip_prefix("save ") {
+                match self.save(path.trim().as_ref()) {
+                    Ok(()) => println!("Game saved to {path}"),
+                    Err(e) => println!("Failed to save game: {e}"),
+                }
+                continue;
+            }
+
+            let guess_raw = raw.trim().to_lowercase();
 
             if guess_raw.is_empty() {
-                println!("{HELPTEXT}");
+                println!("{helptext}");
+                println!("{COMMAND_HELPTEXT}");
+                continue;
+            }
+
+            if guess_raw == "words" {
+                self.show_words()?;
+                continue;
+            }
+
+            if guess_raw == "scores" {
+                self.show_all_scores();
+                continue;
+            }
+
+            if let Some(length) = guess_raw.strip_prefix("new ") {
+                match nonzero(length.trim()) {
+                    Ok(length) => return Ok(Continue(GameAction::New(length))),
+                    Err(e) => {
+                        println!("Invalid length: {e}");
+                        continue;
+                    }
+                }
+            }
+
+            if guess_raw == "reset" {
+                if ask(RESET_QUESTION, false)? {
+                    return Ok(Continue(GameAction::Reset));
+                }
+                continue;
+            }
+
+            if guess_raw == "reveal" {
+                match self.reveal() {
+                    None => println!("No words are left to reveal"),
+                    Some((word, None)) => return Ok(Continue(GameAction::Reveal(word))),
+                    Some((word, Some(remaining))) => {
+                        println!("{remaining}");
+                        if ask(&reveal_question(&word), false)? {
+                            return Ok(Continue(GameAction::Reveal(word)));
+                        }
+                    }
+                }
                 continue;
             }
 
             if guess_raw == "undo" {
-                if self.player.guess_history.is_empty() {
+                if self.player.history().is_empty() {
                     println!("Nothing to undo!");
                     continue;
                 }
 
-                return Ok(Continue(Undo));
+                return Ok(Continue(GameAction::Undo));
             }
 
-            let Some(captures) = self.guess_pattern.captures(&guess_raw) else {
-                println!("Invalid guess format");
-                println!("{HELPTEXT}");
-                continue;
-            };
+            if guess_raw == "redo" {
+                if self.redo_stack.is_empty() {
+                    println!("Nothing to redo!");
+                    continue;
+                }
 
-            let letter = captures.get(1).unwrap().as_str().chars().next().unwrap();
+                return Ok(Continue(GameAction::Redo));
+            }
 
-            if used.contains(&&letter) {
-                println!("{letter} has already been guessed");
-                continue;
+            match self.parse_guess(&raw) {
+                Ok(guesses) => return Ok(Break(guesses)),
+                Err(message) => {
+                    println!("{message}");
+                    if message == INVALID_GUESS {
+                        println!("{helptext}");
+                    }
+                }
             }
+        }
+    }
 
-            let raw_positions = captures.get(2).unwrap();
+    /// Parse a guess typed in the current input mode, explaining what's wrong with it if it can't
+    /// be made
+    fn parse_guess(&self, raw: &str) -> Result<Guesses, String> {
+        let guess_raw = raw.trim().to_lowercase();
+        // keep the case of the letter typed if the dictionary itself is mixed case
+        let guess = if self.player.alphabet().iter().any(|c| c.is_uppercase()) {
+            raw.trim()
+        } else {
+            &guess_raw
+        };
 
-            if raw_positions.is_empty() {
-                return Ok(Break((letter, vec![])));
-            }
+        if let InputMode::Board = self.args.input_mode {
+            return self.parse_board(guess);
+        }
 
-            let positions: Vec<usize> = raw_positions
-                .as_str()
-                .trim()
-                .split(" ")
-                .map(|t| t.parse().unwrap())
-                .collect();
+        // some letters lowercase to several characters, which can never be guessed as one
+        let typed = raw.split_whitespace().next().unwrap_or_default();
+        let folded = guess.split_whitespace().next().unwrap_or_default();
+        if typed.chars().count() == 1 && folded.chars().count() != 1 {
+            Err(format!(
+                "{typed} lowercases to {folded}, which isn't a single letter"
+            ))?;
+        }
 
-            if positions.iter().any(|&p| p == 0 || p > self.args.letters) {
-                println!("Positions provided are invalid letter indicies");
-                continue;
-            }
+        let Some(captures) = self.guess_pattern.captures(guess) else {
+            Err(INVALID_GUESS)?
+        };
 
-            let positions: Vec<_> = positions.into_iter().map(|p| p - 1).collect();
+        let letter = captures.get(1).unwrap().as_str().chars().next().unwrap();
 
-            if let Some(pos) = positions
-                .iter()
-                .find(|&&pos| self.player.current_guess[pos].is_some())
+        if self.player.used_letters().contains(&letter) {
+            Err(format!("{letter} has already been guessed"))?;
+        }
+
+        // the letter may already be on the board without having been guessed, from --known or
+        // because every remaining word had it there, and guessing it reveals those positions too
+        let known: Vec<_> = (0..self.player.current_guess().len())
+            .filter(|&pos| self.player.current_guess()[pos] == Some(letter))
+            .collect();
+
+        let raw_positions = captures.get(2).unwrap();
+
+        if raw_positions.is_empty() {
+            return Ok(vec![(letter, known)]);
+        }
+
+        let positions = parse_positions(raw_positions.as_str())?;
+
+        if positions
+            .iter()
+            .any(|&p| p == 0 || p > self.player.current_guess().len())
+        {
+            Err("Positions provided are invalid letter indicies")?;
+        }
+
+        let mut positions: Vec<_> = positions.into_iter().map(|p| p - 1).collect();
+
+        if let Some(pos) = positions
+            .iter()
+            .find(|&&pos| self.player.current_guess()[pos].is_some_and(|placed| placed != letter))
+        {
+            Err(format!("Letter {} is already occupied", pos + 1))?;
+        }
+
+        positions.extend(known);
+        positions.sort();
+        positions.dedup();
+        Ok(vec![(letter, positions)])
+    }
+
+    /// Make the guess the strategy suggests and answer it from the hidden `word`
+    fn auto_guess(&self, word: &str) -> Result<(char, Vec<usize>), Err> {
+        let letter = self
+            .player
+            .best_guess(self.args.strategy)
+            .ok_or("No letters left to guess")?;
+        println!("Guessing {letter}");
+        let positions = word
+            .chars()
+            .enumerate()
+            .filter_map(|(i, c)| (c == letter).then_some(i))
+            .collect();
+        Ok((letter, positions))
+    }
+
+    /// Work out the guesses made since the last turn from the whole board and every wrong letter
+    /// so far, like `_e__e ast`
+    fn parse_board(&self, input: &str) -> Result<Guesses, String> {
+        let (board, wrong) = input.split_once(' ').unwrap_or((input, ""));
+        let board = parse_pattern(board);
+        let wrong: Vec<_> = wrong
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != ',')
+            .collect();
+        let current = self.player.current_guess();
+        let used = self.player.used_letters();
+
+        if board.len() != current.len() {
+            Err(format!(
+                "The board should have {} letters, like `{}`",
+                current.len(),
+                "_".repeat(current.len())
+            ))?;
+        }
+        if let Some(letter) = board
+            .iter()
+            .flatten()
+            .chain(wrong.iter())
+            .find(|l| !self.player.alphabet().contains(l))
+        {
+            Err(format!("{letter} isn't a letter in any possible word"))?;
+        }
+        for (i, (known, shown)) in current.iter().zip(board.iter()).enumerate() {
+            match (known, shown) {
+                // letters only inferred from the remaining words haven't been revealed in the
+                // game yet, so those positions can still be blank on the board
+                (Some(known), Some(shown)) if shown != known => {
+                    Err(format!("Position {} is already known to be {known}", i + 1))?
+                }
+                (Some(known), None) if used.contains(known) => {
+                    Err(format!("Position {} is already known to be {known}", i + 1))?
+                }
+                (None, Some(shown)) if used.contains(shown) => {
+                    Err(format!("{shown} has already been guessed"))?
+                }
+                _ => {}
+            }
+        }
+
+        // only letters shown in a blank are new, the rest were on the board already
+        let mut letters: Vec<_> = board
+            .iter()
+            .zip(current)
+            .filter_map(|(shown, known)| shown.filter(|_| known.is_none()))
+            .filter(|l| !used.contains(l))
+            .collect();
+        letters.sort();
+        letters.dedup();
+        let mut guesses: Vec<_> = letters
+            .into_iter()
+            .map(|letter| {
+                let positions = (0..board.len())
+                    .filter(|&i| board[i] == Some(letter) || current[i] == Some(letter))
+                    .collect();
+                (letter, positions)
+            })
+            .collect();
+        for &letter in &wrong {
+            if board.contains(&Some(letter)) {
+                Err(format!("{letter} can't be both on the board and wrong"))?;
+            }
+            if !self.player.not_present().contains(&letter)
+                && !guesses.iter().any(|(l, _)| *l == letter)
             {
-                println!("Letter {} is already occupied", pos + 1);
-                continue;
+                if used.contains(&letter) {
+                    Err(format!("{letter} has already been guessed"))?;
+                }
+                guesses.push((letter, vec![]));
+            }
+        }
+
+        if guesses.is_empty() {
+            Err("Nothing has changed since the last guess")?;
+        }
+        Ok(guesses)
+    }
+
+    /// Make each of `guesses` in turn, stopping at the first one that contradicts the board, and
+    /// describe what happened
+    fn apply_guesses(&mut self, guesses: Guesses) -> Vec<String> {
+        let mut lines = Vec::new();
+        for (letter, positions) in guesses {
+            let message = if positions.is_empty() {
+                format!("Letter {letter} is not in the word")
+            } else {
+                format!(
+                    "Letter {letter} is at position(s) {} of the word",
+                    positions
+                        .iter()
+                        .map(|p| (p + 1).to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            };
+            let before = self.player.available_words().len();
+            let hit = !positions.is_empty();
+            match self.player.guess_letter(letter, positions) {
+                Ok(()) => {
+                    lines.push(message);
+                    lines.push(format!(
+                        "Eliminated {} words, {} mistakes so far",
+                        before - self.player.available_words().len(),
+                        self.player.not_present().len()
+                    ));
+                    self.redo_stack.clear();
+                    if self.args.strict
+                        && hit
+                        && before > 0
+                        && self.player.available_words().is_empty()
+                    {
+                        lines.push(format!(
+                            "That placement of {letter} eliminated all candidates — typo?"
+                        ));
+                    }
+                }
+                Err(e) => {
+                    lines.push(e.to_string());
+                    break;
+                }
             }
+        }
+        lines
+    }
+
+    fn undo(&mut self) {
+        self.previous_scores.clear();
+        if let Some(frame) = self.player.undo() {
+            self.redo_stack.push(frame);
+        }
+    }
 
-            return Ok(Break((letter, positions)));
+    fn redo(&mut self) {
+        self.previous_scores.clear();
+        if let Some(frame) = self.redo_stack.pop() {
+            self.player.redo(frame);
         }
     }
 
     pub fn play(&mut self) -> Result<String, Err> {
         loop {
-            self.print_stats();
+            if !self.args.json_suggestions {
+                self.print_stats();
 
-            println!();
-
-            let letter_scores = self.player.compute_letter_scores();
-            self.show_scores_guesses_possibilities(&letter_scores);
+                println!();
+            }
 
-            println!();
+            self.all_scores.clear();
+            match self.args.strategy {
+                _ if self.player.available_words().len() > 1
+                    && !self.player.has_informative_letters() =>
+                {
+                    println!(
+                        "No informative letters remain, {} indistinguishable candidates:",
+                        self.player.available_words().len()
+                    );
+                    for word in self.player.words_by_likelihood() {
+                        println!("{word}");
+                    }
+                    Ok(())
+                }
+                _ if self.player.openers().is_some() => {
+                    let openers: Vec<_> = self
+                        .player
+                        .openers()
+                        .unwrap()
+                        .iter()
+                        .map(|&letter| (letter, Precomputed))
+                        .collect();
+                    self.show_scores_guesses_possibilities(&openers)
+                }
+                Strategy::Frequency => {
+                    self.show_scores_guesses_possibilities(&self.player.compute_letter_scores())
+                }
+                Strategy::Entropy => self.show_scores_guesses_possibilities(
+                    &self
+                        .player
+                        .compute_letter_entropies()
+                        .into_iter()
+                        .map(|(letter, entropy)| (letter, Bits(entropy)))
+                        .collect::<Vec<_>>(),
+                ),
+                Strategy::Minimax => self.show_scores_guesses_possibilities(
+                    &self
+                        .player
+                        .compute_letter_minimax()
+                        .into_iter()
+                        .map(|(letter, worst)| (letter, WorstCase(worst)))
+                        .collect::<Vec<_>>(),
+                ),
+                Strategy::Safe => self.show_scores_guesses_possibilities(
+                    &self
+                        .player
+                        .compute_letter_safety()
+                        .into_iter()
+                        .map(|(letter, safety)| (letter, HitChance(safety)))
+                        .collect::<Vec<_>>(),
+                ),
+            }?;
+
+            if !self.args.json_suggestions {
+                println!();
+            }
 
-            match self.read_guess(&self.player.used_letters)? {
-                Break((letter, positions)) => {
-                    if positions.is_empty() {
-                        println!("Letter {letter} is not in the word");
-                    } else {
-                        println!(
-                            "Letter {letter} is at position(s) {} of the word",
-                            positions
-                                .iter()
-                                .map(|p| (p + 1).to_string())
-                                .collect::<Vec<_>>()
-                                .join(", ")
-                        );
+            *IN_PROGRESS.lock().unwrap() = Some(self.player.game_state());
+            let action = match &self.hidden {
+                Some(word) => Break(vec![self.auto_guess(word)?]),
+                None => self.read_guess()?,
+            };
+            match action {
+                Break(guesses) => {
+                    for line in self.apply_guesses(guesses) {
+                        println!("{line}");
+                    }
+                }
+                Continue(GameAction::Undo) => self.undo(),
+                Continue(GameAction::Redo) => self.redo(),
+                Continue(GameAction::Reset) => {
+                    self.previous_scores.clear();
+                    self.player.reset();
+                    self.redo_stack.clear();
+                    continue;
+                }
+                Continue(GameAction::New(length)) => {
+                    if let Err(e) = self.restart(length) {
+                        println!("{e}");
                     }
-                    self.player.mark_result(letter, positions);
+                    continue;
                 }
-                Continue(Undo) => {
-                    let frame = self.player.guess_history.pop().unwrap();
-                    self.player.current_guess = frame.guess;
-                    self.player.not_present = frame.not_present;
-                    self.player.available_words = self.original_word_list.clone();
+                Continue(GameAction::Reveal(word)) => {
+                    println!("Giving up, the most likely word was {word}");
+                    return Ok(word);
                 }
             }
 
-            self.player.prune_and_fill_certain_letters();
+            if let Some(max_mistakes) = self.args.max_mistakes {
+                if is_lost(self.player.not_present().len(), Some(max_mistakes)) {
+                    println!("{}", gallows(max_mistakes, max_mistakes));
+                    Err(format!("Made {max_mistakes} mistakes, the game is lost!"))?;
+                }
+            }
 
-            match &self.player.available_words[..] {
-                [word] => {
-                    return Ok(word.clone());
+            if let [word] = self.player.available_words() {
+                let word = word.clone();
+                if !self.args.confirm
+                    || self.hidden.is_some()
+                    || ask(&format!("Only {word} is left, is that the word?"), true)?
+                {
+                    return Ok(word);
                 }
-                [] => {
-                    Err("No possible words left! is it in the database / did you make a mistake?")?;
+                self.player.reject_word(&word);
+                self.redo_stack.clear();
+            }
+
+            if self.player.available_words().is_empty() {
+                let closest = self.player.closest_words(self.args.num_closest);
+                if !closest.is_empty() {
+                    println!("Did you mean: {}?", closest.join(", "));
                 }
-                _ => {}
+                Err("No possible words left! is it in the database / did you make a mistake?")?;
             }
         }
     }
 }
 
-fn simulate(words: Vec<String>, word: String) -> Result<SimResults, Err> {
-    let mut player = HangmanPlayer::new(words, word.len())?;
-    let mut mistakes = 0;
-    let mut guesses = Vec::new();
+/// What to ask before clearing every guess with `reset`
+const RESET_QUESTION: &str = "Clear every guess so far and start this word over?";
 
+/// What to ask before giving up with `word` while other words are still possible
+fn reveal_question(word: &str) -> String {
+    format!("Give up and take {word} as the answer?")
+}
+
+/// Ask a yes or no `question` until answered, returning `default` on an empty answer
+fn ask(question: &str, default: bool) -> Result<bool, Err> {
     loop {
-        let scores = player.compute_letter_scores();
-        let letter = scores[0].0; // simulate guess
-        let positions: Vec<_> = word
-            .chars()
-            .enumerate()
-            .filter_map(|(i, c)| (c == letter).then_some(i))
-            .collect(); // simulate receiving the result of the guess
-        if positions.is_empty() {
-            mistakes += 1;
+        print!("{question} {} ", if default { "[Y/n]" } else { "[y/N]" });
+        stdout().flush()?;
+        let mut response = String::new();
+        if read_line(&mut response)? == 0 {
+            return Ok(default);
         }
-        guesses.push(letter);
-        player.mark_result(letter, positions);
-        player.prune_and_fill_certain_letters();
-        match &player.available_words[..] {
-            [single] if single == &word => {
-                player.push_history();
-                return Ok(SimResults {
-                    history: player.guess_history,
-                    guesses,
-                    mistakes,
-                });
-            }
-            [] => Err("No words left")?,
-            [single] => Err(format!("Final result '{single}' is not the correct word"))?,
-            _ => {}
+        match response.trim().to_lowercase().as_str() {
+            "" => return Ok(default),
+            "y" | "yes" => return Ok(true),
+            "n" | "no" => return Ok(false),
+            _ => println!("Type y or n"),
         }
     }
 }
 
-struct Undo;
-
-#[derive(Debug)]
-struct HistoryFrame {
-    guess: Vec<Option<char>>,
-    not_present: Vec<char>,
+/// Lay out ranked letter scores in as many columns as fit in `width`, filled in column by column
+fn score_table(scores: &[(char, String)], width: usize) -> Vec<String> {
+    let cells: Vec<_> = scores
+        .iter()
+        .enumerate()
+        .map(|(i, (letter, score))| format!("{:>2}. {letter}: {score}", i + 1))
+        .collect();
+    let cell_width = cells.iter().map(|c| c.chars().count()).max().unwrap_or(0) + 2;
+    let rows = cells.len().div_ceil((width / cell_width).max(1));
+    (0..rows)
+        .map(|row| {
+            let line: String = cells
+                .iter()
+                .skip(row)
+                .step_by(rows)
+                .map(|cell| format!("{cell:cell_width$}"))
+                .collect();
+            line.trim_end().to_string()
+        })
+        .collect()
 }
 
-struct SimResults {
-    history: Vec<HistoryFrame>,
-    guesses: Vec<char>,
-    mistakes: usize,
+/// A letter's score as shown in the suggestions
+trait Score: Display + Serialize {
+    /// The score as a number, to show how it changed since the last turn
+    fn value(&self) -> Option<f64>;
 }
 
-fn nonzero(arg: &str) -> Result<usize, String> {
-    let val: usize = arg.parse().map_err(|e: ParseIntError| e.to_string())?;
-    if val == 0 {
-        Err("Value must be at least 1!")?;
+impl Score for usize {
+    fn value(&self) -> Option<f64> {
+        Some(*self as f64)
     }
-    Ok(val)
 }
 
-#[derive(Parser)]
-struct Args {
-    /// Name of the file to cache and load words from
-    #[clap(short = 'f', long, default_value = "./words.txt")]
-    words_file: PathBuf,
+/// Stands in for the score of an opening letter loaded from `--openers`
+#[derive(Serialize)]
+struct Precomputed;
 
-    /// Url to load words from if not downloaded
-    #[clap(
-        short = 's',
-        long,
-        default_value = "https://www.mit.edu/~ecprice/wordlist.100000"
-    )]
-    word_source: String,
+impl Score for Precomputed {
+    fn value(&self) -> Option<f64> {
+        None
+    }
+}
+
+impl Display for Precomputed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "precomputed")
+    }
+}
+
+/// Entropy of a guess's outcome, shown in bits
+#[derive(Serialize)]
+#[serde(transparent)]
+struct Bits(f64);
+
+impl Score for Bits {
+    fn value(&self) -> Option<f64> {
+        Some(self.0)
+    }
+}
+
+impl Display for Bits {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.3} bits", self.0)
+    }
+}
+
+/// Most words a guess could leave remaining
+#[derive(Serialize)]
+#[serde(transparent)]
+struct WorstCase(usize);
+
+impl Score for WorstCase {
+    fn value(&self) -> Option<f64> {
+        Some(self.0 as f64)
+    }
+}
+
+impl Display for WorstCase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "at most {} left", self.0)
+    }
+}
+
+/// Chance a guess is in the word
+#[derive(Serialize)]
+#[serde(transparent)]
+struct HitChance(f64);
+
+impl Score for HitChance {
+    fn value(&self) -> Option<f64> {
+        Some(self.0)
+    }
+}
+
+impl Display for HitChance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.1}% to hit", self.0 * 100.0)
+    }
+}
+
+/// State of the board and top suggestions, written each turn with `--json-suggestions`
+#[derive(Serialize)]
+struct TurnSuggestions<'a, S> {
+    current_guess: &'a [Option<char>],
+    not_present: &'a [char],
+    remaining: usize,
+    suggestions: &'a [(char, S)],
+}
+
+/// Letters guessed in a turn, each with the zero-indexed positions it was revealed at
+type Guesses = Vec<(char, Vec<usize>)>;
+
+enum GameAction {
+    Undo,
+    Redo,
+    Reset,
+    New(usize),
+    /// Give up, ending the game with this word
+    Reveal(String),
+}
+
+/// Parse a pattern of known letters like `_pp__`, with `_` for each unknown letter
+fn parse_pattern(pattern: &str) -> Vec<Option<char>> {
+    pattern.chars().map(|c| (c != '_').then_some(c)).collect()
+}
+
+/// Scoring settings given on the command line that apply to every new player
+#[derive(Clone)]
+struct PlayerConfig {
+    frequencies: Option<Arc<Frequencies>>,
+    seed: Option<u64>,
+    score_by: ScoreBy,
+    position_bias: PositionBias,
+    openers: Option<Arc<Openers>>,
+    bigram_threshold: Option<usize>,
+}
+
+impl PlayerConfig {
+    /// Configure `player`, opening with the precomputed letters for `strategy` if there are any
+    fn apply(&self, mut player: HangmanPlayer, strategy: Strategy) -> HangmanPlayer {
+        if let Some(frequencies) = &self.frequencies {
+            player = player.with_frequencies(frequencies.clone());
+        }
+        if let Some(seed) = self.seed {
+            player = player.with_seed(seed);
+        }
+        if let Some(threshold) = self.bigram_threshold {
+            player = player.with_bigram_threshold(threshold);
+        }
+        let length = player.current_guess().len();
+        if let Some(openers) = self
+            .openers
+            .as_ref()
+            .and_then(|openers| openers.get(&strategy)?.get(&length))
+        {
+            player = player.with_openers(openers.clone());
+        }
+        player
+            .with_score_by(self.score_by)
+            .with_position_bias(self.position_bias)
+    }
+}
+
+fn guess_pattern(player: &HangmanPlayer) -> Regex {
+    Regex::new(&format!(
+        r"^([{}])((\s+[0-9,-]+)*)$",
+        regex::escape(&player.alphabet().iter().collect::<String>())
+    ))
+    .unwrap()
+}
+
+/// Read a line from stdin like [`io::Stdin::read_line`]. If Ctrl-C was pressed, the line is instead
+/// taken as a path to save the game in progress to, and the process exits.
+fn read_line(buf: &mut String) -> io::Result<usize> {
+    let read = stdin().read_line(buf)?;
+    if INTERRUPTED.load(Ordering::Relaxed) {
+        let path = buf.trim();
+        if !path.is_empty() {
+            let saved = File::create(path).map_err(Err::from).and_then(|file| {
+                let state = IN_PROGRESS.lock().unwrap();
+                Ok(serde_json::to_writer(BufWriter::new(file), &*state)?)
+            });
+            match saved {
+                Ok(()) => println!("Saved to {path}"),
+                Err(e) => {
+                    eprintln!("Couldn't save to {path}: {e}");
+                    process::exit(1);
+                }
+            }
+        }
+        process::exit(130);
+    }
+    Ok(read)
+}
+
+/// Play without knowing the word's length: every guess is applied to a player for each length in
+/// the dictionary, dropping the lengths it rules out, until a single length is left. Suggestions
+/// add up each letter's scores across the lengths still possible.
+fn narrow_length(
+    words: &[String],
+    args: &PlayArgs,
+    config: &PlayerConfig,
+) -> Result<HangmanPlayer, Err> {
+    let lengths: BTreeSet<_> = words.iter().map(|word| word.chars().count()).collect();
+    let mut players: Vec<_> = lengths
+        .into_iter()
+        .filter_map(|length| HangmanPlayer::new(words, length).ok())
+        .map(|player| config.apply(player, args.strategy))
+        .collect();
+    loop {
+        match players.len() {
+            0 => Err("No word length fits every guess")?,
+            1 => return Ok(players.pop().unwrap()),
+            _ => {}
+        }
+        println!(
+            "possible lengths: {}",
+            players
+                .iter()
+                .map(|player| format!(
+                    "{} ({} words)",
+                    player.current_guess().len(),
+                    player.available_words().len()
+                ))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        let mut scores: HashMap<char, usize> = HashMap::new();
+        for player in &players {
+            for (letter, score) in player.compute_letter_scores() {
+                *scores.entry(letter).or_default() += score;
+            }
+        }
+        let mut scores: Vec<_> = scores.into_iter().collect();
+        scores.sort_by(|(la, a), (lb, b)| b.cmp(a).then(la.cmp(lb)));
+        println!("Top {} guesses:", args.num_suggestions);
+        for (i, (letter, score)) in scores.iter().take(args.num_suggestions).enumerate() {
+            println!("{}. {letter}: {score}", i + 1);
+        }
+        println!();
+
+        print!("Type the letter you guessed, and where it appears in the word: ");
+        stdout().flush()?;
+        let mut raw = String::new();
+        if read_line(&mut raw)? == 0 {
+            Err("No guess given")?;
+        }
+        let mixed_case = players
+            .iter()
+            .any(|player| player.alphabet().iter().any(|c| c.is_uppercase()));
+        let guess = if mixed_case {
+            raw.trim().to_string()
+        } else {
+            raw.trim().to_lowercase()
+        };
+        let (letter, positions) = guess
+            .split_once(char::is_whitespace)
+            .unwrap_or((&guess, ""));
+        let mut letter_chars = letter.chars();
+        let (Some(letter), None) = (letter_chars.next(), letter_chars.next()) else {
+            println!("Type a single letter, followed by the positions it appears at if any");
+            continue;
+        };
+        if !players
+            .iter()
+            .any(|player| player.alphabet().contains(&letter))
+        {
+            println!("{letter} isn't a letter in any possible word");
+            continue;
+        }
+        if players[0].used_letters().contains(&letter) {
+            println!("{letter} has already been guessed");
+            continue;
+        }
+        let positions = match parse_positions(positions) {
+            Ok(positions) if positions.contains(&0) => {
+                println!("Positions start at 1");
+                continue;
+            }
+            Ok(positions) => positions,
+            Err(message) => {
+                println!("{message}");
+                continue;
+            }
+        };
+        let positions: Vec<_> = positions.into_iter().map(|p| p - 1).collect();
+        players.retain_mut(|player| {
+            player.guess_letter(letter, positions.clone()).is_ok()
+                && !player.available_words().is_empty()
+        });
+    }
+}
+
+/// Ask for the word the solver should find by itself
+fn read_hidden_word() -> Result<String, Err> {
+    print!("Type the word for the solver to guess: ");
+    stdout().flush()?;
+    let mut word = String::new();
+    if read_line(&mut word)? == 0 || word.trim().is_empty() {
+        Err("No word given")?;
+    }
+    Ok(word.trim().to_string())
+}
+
+/// Ask how many letters the word has until a valid length is typed
+fn read_length() -> Result<usize, Err> {
+    loop {
+        print!("How many letters are in the word? ");
+        stdout().flush()?;
+        let mut length = String::new();
+        if read_line(&mut length)? == 0 {
+            Err("No word length given")?;
+        }
+        match nonzero(length.trim()) {
+            Ok(length) => return Ok(length),
+            Err(e) => println!("Invalid length: {e}"),
+        }
+    }
+}
+
+/// Parse space or comma separated positions, where each one is either a
+/// single number or an inclusive range like `1-3`
+fn parse_positions(raw: &str) -> Result<Vec<usize>, String> {
+    let mut positions = Vec::new();
+    for token in raw
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|t| !t.is_empty())
+    {
+        let parse = |n: &str| {
+            n.parse::<usize>()
+                .map_err(|_| format!("Invalid position number `{token}`"))
+        };
+        let range = match token.split_once('-') {
+            Some((start, end)) => {
+                let (start, end) = (parse(start)?, parse(end)?);
+                if start > end {
+                    return Err(format!("Invalid position range `{token}`"));
+                }
+                start..=end
+            }
+            None => {
+                let position = parse(token)?;
+                position..=position
+            }
+        };
+        for position in range {
+            if positions.contains(&position) {
+                return Err(format!("Position {position} was given more than once"));
+            }
+            positions.push(position);
+        }
+    }
+    Ok(positions)
+}
+
+/// Pick `amount` distinct words at random, or every word if there are fewer. With `weighted` and
+/// word frequencies loaded, more common words are proportionally more likely to be picked.
+fn sample_words<'w, W: AsRef<str>>(
+    words: &'w [W],
+    amount: usize,
+    weighted: bool,
+    frequencies: &Option<Arc<Frequencies>>,
+    rng: &mut StdRng,
+) -> Result<Vec<&'w W>, Err> {
+    if weighted && frequencies.is_none() {
+        log!(1, "No word frequencies loaded, picking every word equally");
+    }
+    Ok(match frequencies {
+        Some(_) if weighted => words
+            .sample_weighted(rng, amount, |word| {
+                word_weight(frequencies, word.as_ref()) as f64
+            })?
+            .collect(),
+        _ => words.sample(rng, amount).collect(),
+    })
+}
+
+/// Have the user guess a random word of `args.length` letters, revealing where each guessed letter
+/// is, then compare how they did with [`simulate`] playing the same word with `options`
+fn practice(words: &[String], args: &PracticeArgs, options: &SimOptions) -> Result<(), Err> {
+    let candidates: Vec<_> = words
+        .iter()
+        .filter(|word| word.chars().count() == args.length)
+        .collect();
+    let mut rng: StdRng = match options.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => rand::make_rng(),
+    };
+    let sample = sample_words(
+        &candidates,
+        1,
+        args.weighted_random,
+        &options.frequencies,
+        &mut rng,
+    )?;
+    let Some(&&word) = sample.first() else {
+        Err(format!("No {}-letter words in dictionary", args.length))?
+    };
+    let letters: Vec<char> = word.chars().collect();
+    let mut revealed = vec![false; letters.len()];
+    let mut guessed = Vec::new();
+    let mut mistakes = 0;
+    let won = loop {
+        println!(
+            "word: {}",
+            letters
+                .iter()
+                .zip(&revealed)
+                .map(|(letter, &shown)| if shown {
+                    letter.to_string()
+                } else {
+                    "_".into()
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        );
+        if revealed.iter().all(|&shown| shown) {
+            break true;
+        }
+        if let Some(max_mistakes) = args.max_mistakes {
+            println!("{}", gallows(mistakes, max_mistakes));
+            if mistakes >= max_mistakes {
+                break false;
+            }
+        }
+        print!("Guess a letter: ");
+        stdout().flush()?;
+        let mut raw = String::new();
+        if read_line(&mut raw)? == 0 {
+            Err("No guess given")?;
+        }
+        let mut chars = raw.trim().chars().flat_map(char::to_lowercase);
+        let (Some(letter), None) = (chars.next(), chars.next()) else {
+            println!("Type a single letter");
+            continue;
+        };
+        if guessed.contains(&letter) {
+            println!("{letter} has already been guessed");
+            continue;
+        }
+        guessed.push(letter);
+        let mut hit = false;
+        for (shown, &c) in revealed.iter_mut().zip(&letters) {
+            if c == letter {
+                *shown = true;
+                hit = true;
+            }
+        }
+        if !hit {
+            mistakes += 1;
+            println!("{letter} is not in the word, {mistakes} mistakes so far");
+        }
+    };
+    if won {
+        println!("You got it in {} guesses", guessed.len());
+    } else {
+        println!("Out of guesses, the word was {word}");
+    }
+    println!(
+        "You: {} letters guessed, {mistakes} mistakes",
+        guessed.len()
+    );
+    match simulate(words, word, options) {
+        Ok(results) => println!(
+            "Solver: {} letters guessed, {} mistakes, {}",
+            results.guesses.len(),
+            results.mistakes,
+            if results.won {
+                "stopping once it was the only word left"
+            } else {
+                "and lost"
+            }
+        ),
+        Err(e) => println!("The solver couldn't play {word}: {e}"),
+    }
+    Ok(())
+}
+
+/// Parse a transcript of guesses as described for [`Command::Replay`], into each letter and the
+/// zero-indexed positions it was found at, along with the line number each guess is on
+fn parse_transcript(transcript: &str) -> Result<Vec<(usize, char, Vec<usize>)>, Err> {
+    let mut guesses = Vec::new();
+    for (i, line) in transcript.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let bad_line = |e: &str| format!("Transcript line {}: {e}", i + 1);
+        let (letter, result) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let mut letters = letter.chars();
+        let (Some(letter), None) = (letters.next(), letters.next()) else {
+            Err(bad_line(&format!("`{letter}` isn't a single letter")))?
+        };
+        let (outcome, positions) = result
+            .trim_start()
+            .split_once(char::is_whitespace)
+            .unwrap_or((result.trim_start(), ""));
+        let positions = match outcome {
+            "miss" if positions.trim().is_empty() => vec![],
+            "miss" => Err(bad_line("a miss can't have positions"))?,
+            "hit" => {
+                let positions = parse_positions(positions).map_err(|e| bad_line(&e))?;
+                if positions.is_empty() {
+                    Err(bad_line("a hit needs the positions the letter is at"))?;
+                }
+                if positions.contains(&0) {
+                    Err(bad_line("positions start at 1"))?;
+                }
+                positions.into_iter().map(|p| p - 1).collect()
+            }
+            _ => Err(bad_line(
+                "expected `<letter> hit <positions>` or `<letter> miss`",
+            ))?,
+        };
+        if guesses.iter().any(|&(_, guessed, _)| guessed == letter) {
+            Err(bad_line(&format!("{letter} was already guessed")))?;
+        }
+        guesses.push((i + 1, letter, positions));
+    }
+    Ok(guesses)
+}
+
+/// Replay the guesses in `args.transcript`, then show what's known about the word and the guess
+/// to make next
+fn replay(words: &[String], args: &ReplayArgs, config: &PlayerConfig) -> Result<(), Err> {
+    let guesses = parse_transcript(&std::fs::read_to_string(&args.transcript)?)?;
+    let mut player = config.apply(HangmanPlayer::new(words, args.length)?, args.strategy);
+    for (line, letter, positions) in guesses {
+        player
+            .guess_letter(letter, positions)
+            .map_err(|e| format!("Transcript line {line}: {e}"))?;
+        if player.available_words().is_empty() {
+            Err(format!(
+                "Transcript line {line}: no word fits the guesses up to {letter}, is the word in \
+                 the dictionary?"
+            ))?;
+        }
+    }
+
+    println!(
+        "board: {}",
+        player
+            .current_guess()
+            .iter()
+            .map(|letter| letter.map_or("_".to_string(), |l| l.to_string()))
+            .collect::<Vec<_>>()
+            .join(" ")
+    );
+    if !player.not_present().is_empty() {
+        println!(
+            "letters not present: {}",
+            player
+                .not_present()
+                .iter()
+                .map(char::to_string)
+                .collect::<Vec<_>>()
+                .join(" ")
+        );
+    }
+    match player.available_words() {
+        [word] => println!("the word is {word}"),
+        available => {
+            println!("{} possible words", available.len());
+            if let Some(word) = player.most_likely_word() {
+                println!("most likely: {word}");
+            }
+            match player.best_guess(args.strategy) {
+                Some(letter) if player.has_informative_letters() => {
+                    println!("next guess: {letter}")
+                }
+                _ => println!("no guess can tell the remaining words apart"),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Play evil hangman: every guess is answered with the positions that leave the largest family of
+/// words, so the word is only settled once a single one remains
+fn evil(words: &[String], args: &EvilArgs) -> Result<(), Err> {
+    let mut player = HangmanPlayer::new(words, args.length)?;
+    // The player fills in letters every remaining word shares, which the human hasn't earned yet
+    let mut board: Vec<Option<char>> = vec![None; args.length];
+    let mut guessed = Vec::new();
+    let mut mistakes = 0;
+    let won = loop {
+        println!(
+            "word: {}",
+            board
+                .iter()
+                .map(|letter| letter.map_or("_".into(), |letter| letter.to_string()))
+                .collect::<Vec<_>>()
+                .join(" ")
+        );
+        if board.iter().all(Option::is_some) {
+            break true;
+        }
+        if let Some(max_mistakes) = args.max_mistakes {
+            println!("{}", gallows(mistakes, max_mistakes));
+            if mistakes >= max_mistakes {
+                break false;
+            }
+        }
+        print!("Guess a letter: ");
+        stdout().flush()?;
+        let mut raw = String::new();
+        if read_line(&mut raw)? == 0 {
+            Err("No guess given")?;
+        }
+        let mut chars = raw.trim().chars().flat_map(char::to_lowercase);
+        let (Some(letter), None) = (chars.next(), chars.next()) else {
+            println!("Type a single letter");
+            continue;
+        };
+        if guessed.contains(&letter) {
+            println!("{letter} has already been guessed");
+            continue;
+        }
+        guessed.push(letter);
+        let positions = player.largest_family(letter);
+        for &pos in &positions {
+            board[pos] = Some(letter);
+        }
+        if positions.is_empty() {
+            mistakes += 1;
+            println!("{letter} is not in the word, {mistakes} mistakes so far");
+        }
+        player.guess_letter(letter, positions)?;
+        log!(2, "{} words still fit", player.available_words().len());
+    };
+    if won {
+        println!("You got it in {} guesses", guessed.len());
+    } else {
+        match player.available_words() {
+            [word] => println!("Out of guesses, the word was {word}"),
+            words => println!(
+                "Out of guesses, and there was never a word: {} still fit",
+                words.len()
+            ),
+        }
+    }
+    println!(
+        "You: {} letters guessed, {mistakes} mistakes",
+        guessed.len()
+    );
+    Ok(())
+}
+
+/// Draw the gallows with a part of the figure filled in per mistake, scaled so the figure is
+/// complete once `max_mistakes` is reached
+fn gallows(mistakes: usize, max_mistakes: usize) -> String {
+    const PARTS: [(usize, usize, char); 6] = [
+        (2, 2, 'O'),
+        (3, 2, '|'),
+        (3, 1, '/'),
+        (3, 3, '\\'),
+        (4, 1, '/'),
+        (4, 3, '\\'),
+    ];
+    let mut rows: Vec<Vec<char>> = [
+        "  +---+",
+        "  |   |",
+        "      |",
+        "      |",
+        "      |",
+        "      |",
+        "=========",
+    ]
+    .iter()
+    .map(|row| row.chars().collect())
+    .collect();
+    let shown = (mistakes.min(max_mistakes) * PARTS.len()).div_ceil(max_mistakes);
+    for &(row, col, part) in PARTS.iter().take(shown) {
+        rows[row][col] = part;
+    }
+    rows.into_iter()
+        .map(|row| row.into_iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn delimiter(arg: &str) -> Result<u8, String> {
+    match arg {
+        "\\t" => Ok(b'\t'),
+        _ if arg.len() == 1 => Ok(arg.as_bytes()[0]),
+        _ => Err("Delimiter must be a single ascii character, or \\t for tab".to_string()),
+    }
+}
+
+fn nonzero(arg: &str) -> Result<usize, String> {
+    let val: usize = arg.parse().map_err(|e: ParseIntError| e.to_string())?;
+    if val == 0 {
+        Err("Value must be at least 1!")?;
+    }
+    Ok(val)
+}
+
+#[derive(Parser)]
+struct Args {
+    /// Name of the file to cache and load words from. Words are only downloaded and written here
+    /// if it doesn't exist yet, or with --no-cache. Use `-` to read words from stdin, in which
+    /// case `play` can only be used with --tui and a word length. Defaults to `./words.txt` for
+    /// English and `./words-<lang>.txt` for other languages.
+    #[clap(short = 'f', long)]
+    words_file: Option<PathBuf>,
+
+    /// Url to load words from if not downloaded; can be given multiple times to merge several
+    /// lists. Defaults to the list for --lang.
+    #[clap(short = 's', long)]
+    word_source: Vec<String>,
+
+    /// Load the words from every file matching this glob, like `dicts/*.txt`, merging them and
+    /// dropping duplicates. Nothing is downloaded or cached.
+    #[clap(long, conflicts_with_all = ["words_file", "word_source", "no_cache"])]
+    words_glob: Option<String>,
+
+    /// Download the word list even if it's already cached, replacing the cache file once the
+    /// download succeeds
+    #[clap(long)]
+    no_cache: bool,
+
+    /// Times to retry downloading a word list after a network error or server error
+    #[clap(long, default_value_t = 3)]
+    download_retries: usize,
+
+    /// Seconds to wait for a word list download before giving up on it
+    #[clap(long, default_value_t = 30, value_parser = nonzero)]
+    timeout: usize,
+
+    /// Language of the default word list and cache file. The letters that can be guessed always
+    /// come from the words themselves, so they follow the language too.
+    #[clap(long, value_enum, default_value_t)]
+    lang: Lang,
+
+    /// File of `word<TAB>count` lines used to weight common words more heavily when scoring
+    /// letters; without it every word is weighted equally
+    #[clap(long)]
+    frequencies: Option<PathBuf>,
+
+    /// Shuffle letters with tied scores using this seed; by default ties are broken
+    /// alphabetically
+    #[clap(long)]
+    seed: Option<u64>,
+
+    /// Once this few words remain, have the frequency strategy break ties between equally common
+    /// letters by how evenly the positions they'd be revealed at split the remaining words
+    #[clap(long, value_parser = nonzero)]
+    bigram_threshold: Option<usize>,
+
+    /// Open games with the guesses saved by `analyze --precompute-openers` instead of scoring
+    /// letters for the first guess. They should have been computed with the same dictionary,
+    /// frequencies and `--score-by`. They're ranked without a seed or bigram threshold, so can't
+    /// be used with either.
+    #[clap(long, conflicts_with_all = ["seed", "bigram_threshold"])]
+    openers: Option<PathBuf>,
+
+    /// Keep the case of dictionary words as loaded instead of lowercasing them; guesses then have
+    /// to match the case of the words
+    #[clap(long, action = ArgAction::SetTrue)]
+    preserve_case: bool,
+
+    /// Drop dictionary words containing anything but letters, such as digits, hyphens or
+    /// apostrophes
+    #[clap(long, action = ArgAction::SetTrue)]
+    alpha_only: bool,
+
+    /// Keep only this many words of each length: the most common ones according to --frequencies,
+    /// or the first ones in the dictionary without it
+    #[clap(long, value_parser = nonzero)]
+    top_n_words: Option<usize>,
+
+    /// Drop dictionary words shorter than this
+    #[clap(long, value_parser = nonzero)]
+    min_word_length: Option<usize>,
+
+    /// Drop dictionary words longer than this
+    #[clap(long, value_parser = nonzero)]
+    max_word_length: Option<usize>,
+
+    /// How letters are scored by the frequency strategy
+    #[clap(long, value_enum, default_value_t)]
+    score_by: ScoreBy,
+
+    /// Make the frequency strategy favor letters that fill blanks nearer one end of the word, so
+    /// that end is revealed first
+    #[clap(long, value_enum, default_value_t)]
+    position_bias: PositionBias,
+
+    /// Don't print progress messages, only results
+    #[clap(short, long, action = ArgAction::SetTrue, global = true)]
+    quiet: bool,
+
+    /// Print more progress messages; can be repeated
+    #[clap(short, long, action = ArgAction::Count, global = true, conflicts_with = "quiet")]
+    verbose: u8,
 
     #[command(subcommand)]
     command: Command,
@@ -441,18 +1744,76 @@ enum Command {
     /// Play hangman with someone
     Play(PlayArgs),
 
+    /// Guess a word picked at random from the dictionary, the same one each time with --seed, then
+    /// see how the solver would have done
+    Practice(PracticeArgs),
+
+    /// Guess against an opponent that never picks a word, instead answering each guess so that as
+    /// many words as possible still fit
+    Evil(EvilArgs),
+
     /// Simulate playing hangman with a specific word, and show statistics of the result
     Simulate(SimulateArgs),
 
     /// Simulate all words in the dictionary, storing the results in a csv file
     BulkSim(BulkSimArgs),
+
+    /// Simulate all words in the dictionary under several strategies, and compare how they did
+    Bench(BenchArgs),
+
+    /// Check that the solver finds every word in a random sample of the dictionary, the same
+    /// sample each time with --seed, listing any it fails on
+    SelfTest(SelfTestArgs),
+
+    /// Show statistics about the words of each length in the dictionary
+    Analyze(AnalyzeArgs),
+
+    /// List the words in the dictionary matching a pattern
+    Search(SearchArgs),
+
+    /// Show the guesses a strategy would make for words of a given length, branching on each
+    /// possible outcome
+    Tree(TreeArgs),
+
+    /// Print the guesses a strategy opens with for words of a given length: the ones it makes if
+    /// every guess misses, and the ones it makes against the most likely word
+    Opening(OpeningArgs),
+
+    /// Replay the guesses from a game played elsewhere, written one per line as `<letter> hit
+    /// <positions>` or `<letter> miss` with positions starting at 1, then show the board and the
+    /// next guess to make. Blank lines and lines starting with `#` are skipped.
+    Replay(ReplayArgs),
+
+    /// Serve the solver over http. `POST /new {"length": n}` starts a game, `POST /guess
+    /// {"session": id, "letter": "e", "positions": [2, 4]}` records a guess (positions start at 1),
+    /// `GET /state/<id>` shows a game's state, and `DELETE /state/<id>` ends it
+    Serve(ServeArgs),
 }
 
 #[derive(Parser)]
 struct PlayArgs {
-    /// Number of letters in the word being guessed
+    /// Number of letters in the word being guessed; taken from `--known` or asked for when the
+    /// game starts if not given
     #[clap(value_parser = nonzero)]
-    letters: usize,
+    letters: Option<usize>,
+
+    /// Resume a game previously saved with the `save` command
+    #[clap(short, long, conflicts_with = "letters")]
+    resume: Option<PathBuf>,
+
+    /// Letters already known before the game starts, like `_pp__` with `_` for each unknown
+    /// letter. The blanks can still hold the same letters, as with a crossword crossing.
+    #[clap(short, long, conflicts_with = "resume")]
+    known: Option<String>,
+
+    /// Don't assume a word length: apply each guess to every length in the dictionary until only
+    /// one of them is still possible
+    #[clap(
+        long,
+        action = ArgAction::SetTrue,
+        conflicts_with_all = ["letters", "resume", "known", "auto"]
+    )]
+    any_length: bool,
 
     /// Number of top letter suggestions to display
     #[clap(short, long, default_value_t = 5, value_parser = nonzero)]
@@ -461,16 +1822,249 @@ struct PlayArgs {
     /// Show possible words to guess once the total number of possible words goes below this threshold
     #[clap(short, long, default_value_t = 10, value_parser = nonzero)]
     display_guesses_threshold: usize,
+
+    /// Strategy used to rank letter suggestions
+    #[clap(long, value_enum, default_value_t)]
+    strategy: Strategy,
+
+    /// Lose the game once this many guessed letters turn out not to be in the word
+    #[clap(short, long, value_parser = nonzero)]
+    max_mistakes: Option<usize>,
+
+    /// Number of closest dictionary words to suggest if no word matches the guesses
+    #[clap(long, default_value_t = 3)]
+    num_closest: usize,
+
+    /// Check each letter placed against the remaining words, and warn when a placement is what
+    /// left none of them possible
+    #[clap(long, action = ArgAction::SetTrue)]
+    strict: bool,
+
+    /// Once only one word is left, ask whether it's right instead of ending the game, and keep
+    /// guessing without it if not
+    #[clap(long, action = ArgAction::SetTrue)]
+    confirm: bool,
+
+    /// How guesses are typed in each turn
+    #[clap(long, value_enum, default_value_t)]
+    input_mode: InputMode,
+
+    /// Ask for the word once, then let the solver make and answer every guess itself
+    #[clap(long, action = ArgAction::SetTrue)]
+    auto: bool,
+
+    /// When to color the board and suggestions
+    #[clap(long, value_enum, default_value_t)]
+    color: ColorChoice,
+
+    /// Instead of printing the board and suggestions each turn, write them to stderr as a line
+    /// of json
+    #[clap(long, action = ArgAction::SetTrue)]
+    json_suggestions: bool,
+
+    /// Show the most likely letter for each blank position beneath the board
+    #[clap(long, action = ArgAction::SetTrue)]
+    show_positions: bool,
+
+    /// Print how many words and letters are in play and the first suggested guess before
+    /// starting; hidden by --quiet
+    #[clap(long, default_value_t = true, action = ArgAction::Set)]
+    dictionary_stats: bool,
+
+    /// Print how long scoring letters and pruning words took once the game ends
+    #[clap(long, hide = true, action = ArgAction::SetTrue)]
+    timing: bool,
+
+    /// Play in a full screen interface with the board, suggestions and input laid out in fixed
+    /// panels, instead of printing each turn below the last
+    #[clap(long, action = ArgAction::SetTrue, conflicts_with_all = ["auto", "json_suggestions"])]
+    tui: bool,
 }
 
 #[derive(Parser)]
 struct SimulateArgs {
-    /// Word to simulate
-    word: String,
+    /// Words to simulate; with more than one, they're simulated in parallel and a summary table
+    /// of the results is shown
+    #[clap(value_name = "WORD", required_unless_present = "words_from")]
+    words: Vec<String>,
+
+    /// Also simulate each word listed in this file, one per line
+    #[clap(long)]
+    words_from: Option<PathBuf>,
 
     /// Show detailed simulation results
     #[clap(short, long, action = ArgAction::SetTrue)]
     detailed: bool,
+
+    /// Strategy used to choose each guess
+    #[clap(long, value_enum, default_value_t)]
+    strategy: Strategy,
+
+    /// Count the word as lost once this many guessed letters turn out not to be in it
+    #[clap(short, long, value_parser = nonzero)]
+    max_mistakes: Option<usize>,
+
+    /// Format to print the results in
+    #[clap(long, value_enum, default_value_t)]
+    format: OutputFormat,
+
+    /// Play back each turn of the solve one at a time, in text output
+    #[clap(long, action = ArgAction::SetTrue)]
+    animate: bool,
+
+    /// Milliseconds to pause between turns when animating
+    #[clap(long, default_value_t = 500, requires = "animate")]
+    delay: u64,
+
+    /// Write a csv row for every turn of every word simulated to this file
+    #[clap(long)]
+    log: Option<PathBuf>,
+
+    /// Print how long scoring letters and pruning words took across every simulated turn
+    #[clap(long, hide = true, action = ArgAction::SetTrue)]
+    timing: bool,
+}
+
+/// Language with a bundled default word list
+#[derive(Clone, Copy, Default, ValueEnum)]
+enum Lang {
+    #[default]
+    En,
+    Fr,
+    De,
+    Es,
+}
+
+impl Lang {
+    /// Where to download this language's words from when no --word-source is given
+    fn word_source(self) -> &'static str {
+        match self {
+            Lang::En => "https://www.mit.edu/~ecprice/wordlist.100000",
+            Lang::Fr => "https://raw.githubusercontent.com/lorenbrichter/Words/master/Words/fr.txt",
+            Lang::De => "https://raw.githubusercontent.com/lorenbrichter/Words/master/Words/de.txt",
+            Lang::Es => "https://raw.githubusercontent.com/lorenbrichter/Words/master/Words/es.txt",
+        }
+    }
+
+    /// Where to cache this language's words when no --words-file is given
+    fn words_file(self) -> PathBuf {
+        match self {
+            Lang::En => "./words.txt".into(),
+            lang => format!(
+                "./words-{}.txt",
+                lang.to_possible_value().unwrap().get_name()
+            )
+            .into(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, ValueEnum)]
+enum OutputFormat {
+    /// Human readable summary
+    #[default]
+    Text,
+
+    /// Full results, including every turn, as json
+    Json,
+}
+
+#[derive(Clone, Copy, Default, ValueEnum)]
+enum InputMode {
+    /// Type the letter guessed and the positions it was revealed at, like `e 2 4`
+    #[default]
+    Incremental,
+
+    /// Type the whole board and every wrong letter so far, like `_e__e ast`, and let the changes
+    /// be worked out
+    Board,
+}
+
+#[derive(Clone, Copy, Default, ValueEnum)]
+enum ColorChoice {
+    /// Only when printing to a terminal
+    #[default]
+    Auto,
+
+    Always,
+
+    Never,
+}
+
+/// One turn of a simulated game, as written by `simulate --log`
+#[derive(Serialize)]
+struct TurnRecord<'a> {
+    word: &'a str,
+    turn: usize,
+    letter: char,
+    hit: bool,
+    remaining: usize,
+    mistakes: usize,
+}
+
+/// Print `tree` indented by `depth`, one line per node, each labelled with the outcome of the
+/// `parent` guess that led to it
+fn print_guess_tree(tree: &GuessTree, parent: Option<(char, &[usize])>, depth: usize) {
+    let outcome = match parent {
+        None => String::new(),
+        Some((letter, [])) => format!("no {letter}: "),
+        Some((letter, positions)) => format!(
+            "{letter} at {}: ",
+            positions
+                .iter()
+                .map(|p| (p + 1).to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+    let words = match tree.remaining {
+        1 => "1 word".to_string(),
+        n => format!("{n} words"),
+    };
+    let next = match (&tree.word, tree.guess) {
+        (Some(word), _) => format!(", {word}"),
+        (None, Some(guess)) => format!(", guess {guess}"),
+        (None, None) => String::new(),
+    };
+    println!("{}{outcome}{words}{next}", "  ".repeat(depth));
+    if let Some(guess) = tree.guess {
+        for branch in &tree.branches {
+            print_guess_tree(&branch.tree, Some((guess, &branch.positions)), depth + 1);
+        }
+    }
+}
+
+/// Write every turn of each of `results` to a csv file at `path`
+fn write_turn_log(path: &Path, results: &[(String, SimResults)]) -> Result<(), Err> {
+    let mut writer = csv::WriterBuilder::new().from_path(path)?;
+    for (word, results) in results {
+        let mut mistakes = 0;
+        for (turn, (&letter, &remaining)) in
+            (1..).zip(results.guesses.iter().zip(&results.remaining))
+        {
+            let hit = word.contains(letter);
+            if !hit {
+                mistakes += 1;
+            }
+            writer.serialize(TurnRecord {
+                word,
+                turn,
+                letter,
+                hit,
+                remaining,
+                mistakes,
+            })?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct WordSimResults<'a> {
+    word: &'a str,
+    #[serde(flatten)]
+    results: &'a SimResults,
 }
 
 #[derive(Parser)]
@@ -478,101 +2072,1238 @@ struct BulkSimArgs {
     /// Output file
     #[clap(short, long, default_value = "scores.csv")]
     out: PathBuf,
+
+    /// Overwrite the output file if it already exists
+    #[clap(long, action = ArgAction::SetTrue, conflicts_with = "resume")]
+    force: bool,
+
+    /// Continue an interrupted run from the output file: skip the words already in it, and append
+    /// the rest to it
+    #[clap(long, action = ArgAction::SetTrue)]
+    resume: bool,
+
+    /// Number of worker threads to simulate with, defaults to all available cores
+    #[clap(short, long, value_parser = nonzero)]
+    jobs: Option<usize>,
+
+    /// Strategy used to choose each guess
+    #[clap(long, value_enum, default_value_t)]
+    strategy: Strategy,
+
+    /// Count the word as lost once this many guessed letters turn out not to be in it
+    #[clap(short, long, value_parser = nonzero)]
+    max_mistakes: Option<usize>,
+
+    /// Sort the output by difficulty, hardest words first, instead of writing rows as they finish
+    #[clap(long, action = ArgAction::SetTrue)]
+    sort: bool,
+
+    /// Print histograms of the guesses and mistakes taken to stderr once done; on by default when
+    /// stdout is a terminal
+    #[clap(long, num_args = 0..=1, default_missing_value = "true")]
+    summary: Option<bool>,
+
+    /// Only simulate words with at least this many letters
+    #[clap(long, value_parser = nonzero)]
+    min_length: Option<usize>,
+
+    /// Only simulate words with at most this many letters
+    #[clap(long, value_parser = nonzero)]
+    max_length: Option<usize>,
+
+    /// Replay every word's game from scratch instead of sharing the guesses common to words that
+    /// have given the same results so far. Gives identical results, only slower; words of the same
+    /// length are otherwise simulated on one thread.
+    #[clap(long, action = ArgAction::SetTrue)]
+    naive: bool,
+
+    /// Remember how letters were ranked in up to this many game states, so games that reach a
+    /// state already scored reuse it; 0 turns this off. Only used with --naive, since otherwise
+    /// every state is only reached once, and by the frequency strategy only once
+    /// --bigram-threshold applies, as its plain scores are cheaper to rank than to look up.
+    #[clap(long, default_value_t = 100_000)]
+    score_cache: usize,
+
+    /// Also simulate every word with this strategy, adding the guesses and mistakes it took as
+    /// two extra columns
+    #[clap(long, value_enum)]
+    compare_strategy: Option<Strategy>,
+
+    /// Character separating the columns of the output file, such as `\t` for tab separated values
+    #[clap(long, default_value = ",", value_parser = delimiter)]
+    delimiter: u8,
+}
+
+#[derive(Parser)]
+struct BenchArgs {
+    /// Strategies to compare
+    #[clap(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        num_args = 1..,
+        default_values_t = [Strategy::Frequency, Strategy::Entropy]
+    )]
+    strategies: Vec<Strategy>,
+
+    /// Also write each word's results, prefixed with the strategy name, to this csv file
+    #[clap(short, long)]
+    out: Option<PathBuf>,
+
+    /// Number of worker threads to simulate with, defaults to all available cores
+    #[clap(short, long, value_parser = nonzero)]
+    jobs: Option<usize>,
+
+    /// Count the word as lost once this many guessed letters turn out not to be in it
+    #[clap(short, long, value_parser = nonzero)]
+    max_mistakes: Option<usize>,
+}
+
+#[derive(Parser)]
+struct SearchArgs {
+    /// Known letters of the word, with `_` for each unknown one, like `_a___`
+    pattern: String,
+
+    /// Letters the word must not contain
+    #[clap(short, long, default_value = "")]
+    exclude: String,
+
+    /// Letters the word must contain somewhere
+    #[clap(short, long, default_value = "")]
+    include: String,
+}
+
+#[derive(Parser)]
+struct PracticeArgs {
+    /// Number of letters in the word to guess
+    #[clap(value_parser = nonzero)]
+    length: usize,
+
+    /// Lose once this many guessed letters turn out not to be in the word
+    #[clap(short, long, value_parser = nonzero)]
+    max_mistakes: Option<usize>,
+
+    /// Strategy the solver plays the same word with, to compare against
+    #[clap(long, value_enum, default_value_t)]
+    strategy: Strategy,
+
+    /// Pick the word with more common words more likely, by the loaded word frequencies;
+    /// without frequencies every word is equally likely
+    #[clap(long, action = ArgAction::SetTrue)]
+    weighted_random: bool,
+}
+
+#[derive(Parser)]
+struct EvilArgs {
+    /// Number of letters in the word to guess
+    #[clap(value_parser = nonzero)]
+    length: usize,
+
+    /// Lose once this many guessed letters turn out not to be in the word
+    #[clap(short, long, value_parser = nonzero)]
+    max_mistakes: Option<usize>,
+}
+
+#[derive(Parser)]
+struct TreeArgs {
+    /// Number of letters in the words
+    #[clap(value_parser = nonzero)]
+    length: usize,
+
+    /// Number of guesses deep to follow each branch
+    #[clap(long, default_value_t = 2, value_parser = nonzero)]
+    max_depth: usize,
+
+    /// Strategy used to choose each guess
+    #[clap(long, value_enum, default_value_t)]
+    strategy: Strategy,
+
+    /// Format to print the tree in
+    #[clap(long, value_enum, default_value_t)]
+    format: OutputFormat,
+}
+
+#[derive(Parser)]
+struct SelfTestArgs {
+    /// Number of words to check; every word is checked if the dictionary has fewer
+    #[clap(short = 'n', long, default_value_t = 1000, value_parser = nonzero)]
+    sample: usize,
+
+    /// Sample more common words more often, by the loaded word frequencies; without frequencies
+    /// every word is equally likely
+    #[clap(long, action = ArgAction::SetTrue)]
+    weighted_random: bool,
+
+    /// Strategy used to choose each guess
+    #[clap(long, value_enum, default_value_t)]
+    strategy: Strategy,
+
+    /// Number of worker threads to simulate with, defaults to all available cores
+    #[clap(short, long, value_parser = nonzero)]
+    jobs: Option<usize>,
+}
+
+#[derive(Parser)]
+struct ReplayArgs {
+    /// Number of letters in the word
+    #[clap(value_parser = nonzero)]
+    length: usize,
+
+    /// File the guesses are read from
+    transcript: PathBuf,
+
+    /// Strategy used to choose the next guess
+    #[clap(long, value_enum, default_value_t)]
+    strategy: Strategy,
+}
+
+#[derive(Parser)]
+struct OpeningArgs {
+    /// Number of letters in the words
+    #[clap(value_parser = nonzero)]
+    length: usize,
+
+    /// Strategy used to choose each guess
+    #[clap(long, value_enum, default_value_t)]
+    strategy: Strategy,
+}
+
+#[derive(Parser)]
+struct AnalyzeArgs {
+    /// Also write the statistics to this csv file
+    #[clap(short, long)]
+    out: Option<PathBuf>,
+
+    /// Work out the best opening guesses for each word length under every strategy and save them
+    /// to this file, to be loaded with `--openers`
+    #[clap(long)]
+    precompute_openers: Option<PathBuf>,
+
+    /// Number of opening guesses to save for each word length
+    #[clap(long, default_value_t = 5, value_parser = nonzero)]
+    num_openers: usize,
+}
+
+/// The board of `frame` followed by the letters known to be missing, like `_ e _, [a s]`
+fn format_frame(frame: &HistoryFrame) -> String {
+    format!(
+        "{}, [{}]",
+        frame
+            .guess
+            .iter()
+            .map(|letter| match letter {
+                None => "_".to_string(),
+                Some(letter) => (*letter).into(),
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+        frame
+            .not_present
+            .iter()
+            .cloned()
+            .map(String::from)
+            .collect::<Vec<_>>()
+            .join(" ")
+    )
+}
+
+/// Play back each turn of `results`, redrawing the board in place and pausing `delay` between
+/// turns. When stdout isn't a terminal, each turn is printed on its own line without pausing.
+fn animate_sim(results: &SimResults, delay: Duration) -> Result<(), Err> {
+    let redraw = stdout().is_terminal();
+    for (i, frame) in results.history.iter().enumerate() {
+        let line = match results.guesses.get(i) {
+            Some(guess) => format!("Turn {}: {}, guessing {guess}", i + 1, format_frame(frame)),
+            None => format!("Turn {}: {}", i + 1, format_frame(frame)),
+        };
+        if redraw {
+            reprint!("{line}");
+            stdout().flush()?;
+            thread::sleep(delay);
+        } else {
+            println!("{line}");
+        }
+    }
+    if redraw {
+        println!();
+    }
+    Ok(())
+}
+
+fn print_sim_results(results: &SimResults, detailed: bool) {
+    if results.won {
+        println!(
+            "Took {} guesses to guess the word, making {} total mistakes",
+            results.history.len(),
+            results.mistakes
+        );
+    } else {
+        println!(
+            "Failed to guess the word, making {} mistakes in {} guesses",
+            results.mistakes,
+            results.guesses.len()
+        );
+    }
+
+    if detailed {
+        for ((i, frame), guess) in (1..)
+            .zip(results.history.iter())
+            .zip(results.guesses.iter())
+        {
+            println!("Turn {i}: {}, guessed {guess}", format_frame(frame));
+        }
+    }
+}
+
+fn print_sim_summary(results: &[(String, SimResults)]) {
+    let width = results
+        .iter()
+        .map(|(word, _)| word.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("average".len());
+    println!("{:width$}  guesses  mistakes  won", "word");
+    for (word, results) in results {
+        println!(
+            "{word:width$}  {:>7}  {:>8}  {:>3}",
+            results.history.len(),
+            results.mistakes,
+            if results.won { "yes" } else { "no" }
+        );
+    }
+    let total_guesses: usize = results.iter().map(|(_, r)| r.history.len()).sum();
+    let total_mistakes: usize = results.iter().map(|(_, r)| r.mistakes).sum();
+    let wins = results.iter().filter(|(_, r)| r.won).count();
+    println!(
+        "{:width$}  {total_guesses:>7}  {total_mistakes:>8}  {wins:>3}",
+        "total"
+    );
+    println!(
+        "{:width$}  {:>7.2}  {:>8.2}  {:>3.0}%",
+        "average",
+        total_guesses as f64 / results.len() as f64,
+        total_mistakes as f64 / results.len() as f64,
+        wins as f64 / results.len() as f64 * 100.0
+    );
+}
+
+#[derive(Parser)]
+struct ServeArgs {
+    /// Address to listen on
+    #[clap(short, long, default_value = "127.0.0.1:8080")]
+    address: String,
+
+    /// Number of top letter suggestions to include in each response
+    #[clap(short, long, default_value_t = 5, value_parser = nonzero)]
+    num_suggestions: usize,
+
+    /// Most games to keep at once. Starting another one past this ends the game that has gone
+    /// longest without a request
+    #[clap(long, default_value_t = 1000, value_parser = nonzero)]
+    max_sessions: usize,
+}
+
+/// Simulate every target word in parallel, calling `on_result` on the current thread with each
+/// word's results as they finish
+fn simulate_all(
+    words: &[String],
+    targets: &[&String],
+    options: &SimOptions,
+    jobs: Option<usize>,
+    memoize: bool,
+    mut on_result: impl FnMut(String, Result<SimResults, String>) -> Result<(), Err>,
+) -> Result<(), Err> {
+    let (send, recv) = channel();
+    let completed = AtomicUsize::new(0);
+
+    thread::scope(|s| -> Result<(), Err> {
+        s.spawn(|| {
+            let pool = ThreadPoolBuilder::new()
+                .num_threads(jobs.unwrap_or(0))
+                .build()
+                .unwrap();
+            pool.install(|| {
+                if memoize {
+                    let mut lengths: BTreeMap<usize, Vec<&str>> = BTreeMap::new();
+                    for word in targets {
+                        lengths.entry(word.chars().count()).or_default().push(word);
+                    }
+                    lengths
+                        .into_par_iter()
+                        .for_each_with(send, |send, (length, group)| {
+                            simulate_tree(words, length, &group, options, &mut |word, results| {
+                                completed.fetch_add(1, Ordering::Relaxed);
+                                send.send((word.to_string(), results)).unwrap();
+                            })
+                        });
+                } else {
+                    targets.par_iter().for_each_with(send, |send, &word| {
+                        let results = simulate(words, word, options).map_err(|e| e.to_string());
+                        completed.fetch_add(1, Ordering::Relaxed);
+                        send.send((word.clone(), results)).unwrap();
+                    })
+                }
+            });
+        });
+
+        let start = Instant::now();
+        for ((word, results), log) in recv
+            .into_iter()
+            .zip(Observer::new(Duration::from_secs_f32(0.1)))
+        {
+            if log {
+                let done = completed.load(Ordering::Relaxed);
+                let rate = done as f64 / start.elapsed().as_secs_f64();
+                let eta = Duration::from_secs_f64((targets.len() - done) as f64 / rate);
+                progress!(
+                    "{done}/{} — {rate:.0}/s — ETA {}",
+                    targets.len(),
+                    clock_time(eta)
+                );
+            }
+            on_result(word, results)?;
+        }
+        Ok(())
+    })
+}
+
+/// `duration` to the second, as `m:ss`, or `h:mm:ss` once it's an hour or more
+fn clock_time(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs / 3600 {
+        0 => format!("{}:{:02}", secs / 60, secs % 60),
+        hours => format!("{hours}:{:02}:{:02}", secs / 60 % 60, secs % 60),
+    }
+}
+
+/// Print a histogram of `values` to stderr, followed by their mean, median and max
+fn print_histogram(label: &str, values: &mut [usize]) {
+    const BAR_WIDTH: usize = 40;
+    if values.is_empty() {
+        return;
+    }
+    values.sort();
+    let mut counts: Vec<(usize, usize)> = Vec::new();
+    for run in values.chunk_by(|a, b| a == b) {
+        counts.push((run[0], run.len()));
+    }
+    let most = counts.iter().map(|&(_, count)| count).max().unwrap_or(1);
+    let width = values[values.len() - 1].to_string().len();
+    eprintln!("{label}:");
+    for (value, count) in counts {
+        eprintln!(
+            "  {value:>width$} | {} {count}",
+            "#".repeat((count * BAR_WIDTH).div_ceil(most))
+        );
+    }
+    eprintln!(
+        "  mean {:.2}, median {}, max {}",
+        values.iter().sum::<usize>() as f64 / values.len() as f64,
+        values[values.len() / 2],
+        values[values.len() - 1]
+    );
+}
+
+/// Print the number of calls to each phase in `timings` and how long they took, to stderr
+fn print_timings(timings: &Timings) {
+    for (phase, durations) in [("scoring", &timings.scoring), ("pruning", &timings.pruning)] {
+        let Some(max) = durations.iter().max() else {
+            eprintln!("{phase}: never called");
+            continue;
+        };
+        let min = durations.iter().min().unwrap();
+        let total: Duration = durations.iter().sum();
+        eprintln!(
+            "{phase}: {} calls, total {}µs, mean {:.1}µs, min {}µs, max {}µs",
+            durations.len(),
+            total.as_micros(),
+            total.as_secs_f64() * 1e6 / durations.len() as f64,
+            min.as_micros(),
+            max.as_micros()
+        );
+    }
+}
+
+/// Simulate each of `targets` in parallel, returning their results in the same order
+fn simulate_words(
+    words: &[String],
+    targets: &[String],
+    options: &SimOptions,
+) -> Result<Vec<(String, SimResults)>, Err> {
+    let mut finished = HashMap::new();
+    simulate_all(
+        words,
+        &targets.iter().collect::<Vec<_>>(),
+        options,
+        None,
+        false,
+        |word, results| {
+            finished.insert(word, results);
+            Ok(())
+        },
+    )?;
+    log!(1, "");
+    targets
+        .iter()
+        .map(|word| match &finished[word] {
+            Ok(results) => Ok((word.clone(), results.clone())),
+            Err(e) => Err(format!("Failed to simulate {word}: {e}").into()),
+        })
+        .collect()
+}
+
+/// Totals for one strategy's run over the dictionary
+#[derive(Default)]
+struct BenchSummary {
+    simulated: usize,
+    failures: usize,
+    guesses: usize,
+    mistakes: usize,
+    wins: usize,
+    worst: Option<(String, usize)>,
+}
+
+impl BenchSummary {
+    fn add(&mut self, word: &str, results: &Result<SimResults, String>) {
+        let Ok(results) = results else {
+            self.failures += 1;
+            return;
+        };
+        self.simulated += 1;
+        self.guesses += results.history.len();
+        self.mistakes += results.mistakes;
+        if results.won {
+            self.wins += 1;
+        }
+        let difficulty = results.difficulty();
+        if self.worst.as_ref().is_none_or(|(_, d)| difficulty > *d) {
+            self.worst = Some((word.to_string(), difficulty));
+        }
+    }
+}
+
+fn print_bench_summary(summaries: &[(String, BenchSummary)], show_win_rate: bool) {
+    let width = summaries
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0)
+        .max("strategy".len());
+    let worst_width = summaries
+        .iter()
+        .filter_map(|(_, s)| s.worst.as_ref())
+        .map(|(word, _)| word.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("worst word".len());
+    print!(
+        "{:width$}  avg guesses  avg mistakes  {:worst_width$}  failures",
+        "strategy", "worst word"
+    );
+    if show_win_rate {
+        print!("  win rate");
+    }
+    println!();
+    for (name, summary) in summaries {
+        let simulated = summary.simulated.max(1) as f64;
+        print!(
+            "{name:width$}  {:>11.2}  {:>12.2}  {:worst_width$}  {:>8}",
+            summary.guesses as f64 / simulated,
+            summary.mistakes as f64 / simulated,
+            summary
+                .worst
+                .as_ref()
+                .map_or("-", |(word, _)| word.as_str()),
+            summary.failures
+        );
+        if show_win_rate {
+            print!("  {:>7.1}%", summary.wins as f64 / simulated * 100.0);
+        }
+        println!();
+    }
+}
+
+/// One row of `bulk-sim` output; fields left empty when the word failed to simulate
+#[derive(Serialize)]
+struct SimRecord {
+    word: String,
+    length: usize,
+    guesses: Option<usize>,
+    mistakes: Option<usize>,
+    won: Option<bool>,
+    difficulty: Option<usize>,
+    status: String,
+}
+
+/// Guesses and mistakes taken on the same word by `bulk-sim --compare-strategy`
+#[derive(Clone, Copy, Serialize)]
+struct CompareRecord {
+    #[serde(rename = "compare_guesses")]
+    guesses: Option<usize>,
+    #[serde(rename = "compare_mistakes")]
+    mistakes: Option<usize>,
 }
 
+/// The strategy column leading each row of `bench --out`
 #[derive(Serialize)]
-struct SimRecord(String, usize, usize);
+struct BenchStrategy<'a> {
+    strategy: &'a str,
+}
+
+/// Write `row` to `writer`, followed by the compared strategy's columns if there are any
+fn write_sim_row(
+    writer: &mut csv::Writer<File>,
+    row: SimRecord,
+    compare: Option<CompareRecord>,
+) -> Result<(), Err> {
+    match compare {
+        Some(compare) => writer.serialize((row, compare))?,
+        None => writer.serialize(row)?,
+    }
+    Ok(())
+}
+
+impl SimRecord {
+    fn new(word: String, results: Result<SimResults, String>) -> SimRecord {
+        let length = word.chars().count();
+        match results {
+            Ok(results) => SimRecord {
+                word,
+                length,
+                guesses: Some(results.history.len()),
+                mistakes: Some(results.mistakes),
+                won: Some(results.won),
+                difficulty: Some(results.difficulty()),
+                status: "ok".to_string(),
+            },
+            Err(e) => SimRecord {
+                word,
+                length,
+                guesses: None,
+                mistakes: None,
+                won: None,
+                difficulty: None,
+                status: format!("error: {e}"),
+            },
+        }
+    }
+}
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("Error: {e}");
+        process::exit(1);
+    }
+}
 
-fn main() -> Result<(), Err> {
+fn run() -> Result<(), Err> {
     let args = Args::parse();
-    let words = load_words(&args)?;
-    println!("Loaded {} words", words.len());
+    VERBOSITY.store(
+        if args.quiet { 0 } else { 1 + args.verbose },
+        Ordering::Relaxed,
+    );
+    let words_file = args
+        .words_file
+        .clone()
+        .unwrap_or_else(|| args.lang.words_file());
+    // words piped in use up stdin, so only the tui can still take input once the length is known
+    if let (Command::Play(play), None) = (&args.command, &args.words_glob) {
+        if words_file == Path::new("-")
+            && !(play.tui
+                && (play.letters.is_some() || play.known.is_some() || play.resume.is_some()))
+        {
+            Err(
+                "`play` reads guesses from stdin, so with words from `-f -` it needs --tui and \
+                 the word length given by <LETTERS>, --known or --resume",
+            )?;
+        }
+    }
+    let word_sources = if args.word_source.is_empty() {
+        vec![args.lang.word_source().to_string()]
+    } else {
+        args.word_source.clone()
+    };
+    let download = DownloadOptions {
+        refresh: args.no_cache,
+        retries: args.download_retries,
+        timeout: Some(Duration::from_secs(args.timeout as u64)),
+    };
+    let words = match &args.words_glob {
+        Some(pattern) => load_words_glob(pattern, VERBOSITY.load(Ordering::Relaxed) >= 1)?,
+        None => load_words(
+            &words_file,
+            &word_sources,
+            &download,
+            VERBOSITY.load(Ordering::Relaxed) >= 1,
+        )?,
+    };
+    let words = if args.preserve_case {
+        words
+    } else {
+        lowercase_words(words)
+    };
+    log!(1, "Loaded {} words", words.len());
+    let loaded = words.len();
+    let words: Vec<_> = words
+        .into_iter()
+        .filter(|word| {
+            let length = word.chars().count();
+            (!args.alpha_only || word.chars().all(char::is_alphabetic))
+                && args.min_word_length.is_none_or(|min| length >= min)
+                && args.max_word_length.is_none_or(|max| length <= max)
+        })
+        .collect();
+    if words.len() < loaded {
+        log!(
+            1,
+            "Filtered out {} words, leaving {}",
+            loaded - words.len(),
+            words.len()
+        );
+    }
+    let frequencies = args
+        .frequencies
+        .as_deref()
+        .map(load_frequencies)
+        .transpose()?
+        .map(Arc::new);
+    if let Some(frequencies) = &frequencies {
+        log!(2, "Loaded frequencies for {} words", frequencies.len());
+    }
+    let words = match args.top_n_words {
+        Some(n) => {
+            let loaded = words.len();
+            let words = top_words(words, n, frequencies.as_deref());
+            log!(
+                1,
+                "Kept the top {n} words of each length, dropping {}",
+                loaded - words.len()
+            );
+            words
+        }
+        None => words,
+    };
+
+    let seed = args.seed;
+    let openers = args
+        .openers
+        .as_deref()
+        .map(|path| -> Result<Openers, Err> {
+            Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?)
+        })
+        .transpose()?
+        .map(Arc::new);
+    let config = PlayerConfig {
+        frequencies: frequencies.clone(),
+        seed,
+        score_by: args.score_by,
+        position_bias: args.position_bias,
+        openers,
+        bigram_threshold: args.bigram_threshold,
+    };
 
     match args.command {
         Command::Play(args) => {
-            let mut game = PlayerUI::new(HangmanPlayer::new(words, args.letters)?, args);
-            let final_guess = game.play()?;
-            println!("Final guess: {final_guess}");
+            ctrlc::set_handler(|| {
+                if INTERRUPTED.swap(true, Ordering::Relaxed)
+                    || IN_PROGRESS.lock().unwrap().is_none()
+                {
+                    process::exit(130);
+                }
+                print!(
+                    "\nSave game before quitting? Type a path to save to, or hit enter to quit: "
+                );
+                let _ = stdout().flush();
+            })?;
+            let mut game = PlayerUI::new(&words, args, config)?;
+            let final_guess = if game.args.tui {
+                tui::play(&mut game)?
+            } else {
+                Some(game.play()?)
+            };
+            if let Some(final_guess) = final_guess {
+                println!("Final guess: {final_guess}");
+            }
+            if let Some(timings) = game.player.timings() {
+                print_timings(&timings);
+            }
         }
         Command::Simulate(args) => {
-            let results = simulate(words, args.word)?;
-            println!(
-                "Took {} guesses to guess the word, making {} total mistakes",
-                results.history.len(),
-                results.mistakes
-            );
-
-            if args.detailed {
-                for ((i, frame), guess) in (1..).zip(results.history).zip(results.guesses) {
-                    println!(
-                        "Turn {i}: {}, [{}], guessed {guess}",
-                        frame
-                            .guess
-                            .iter()
-                            .map(|letter| match letter {
-                                None => "_".to_string(),
-                                Some(letter) => (*letter).into(),
-                            })
-                            .collect::<Vec<_>>()
-                            .join(" "),
-                        frame
-                            .not_present
-                            .iter()
-                            .cloned()
-                            .map(String::from)
-                            .collect::<Vec<_>>()
-                            .join(" ")
-                    );
+            let options = SimOptions {
+                strategy: args.strategy,
+                score_by: config.score_by,
+                position_bias: config.position_bias,
+                max_mistakes: args.max_mistakes,
+                frequencies,
+                seed,
+                openers: config.openers.clone(),
+                bigram_threshold: config.bigram_threshold,
+                timing: args.timing,
+                score_cache: None,
+            };
+            let mut targets = args.words;
+            if let Some(path) = &args.words_from {
+                for line in BufReader::new(File::open(path)?).lines() {
+                    let line = line?;
+                    if !line.trim().is_empty() {
+                        targets.push(line.trim().to_string());
+                    }
                 }
             }
-        }
-        Command::BulkSim(args) => {
-            let (send, recv) = channel();
-
-            thread::scope(|s| -> Result<(), Err> {
-                s.spawn({
-                    let words = words.clone();
-                    let send = send.clone();
-                    move || {
-                        let pool = ThreadPool::new(8);
-                        for word in words.iter() {
-                            let word = word.clone();
-                            let words = words.clone();
-                            let send = send.clone();
-                            pool.execute(move || {
-                                send.send((word.clone(), simulate(words, word).unwrap()))
-                                    .unwrap()
-                            });
+            if let [word] = &targets[..] {
+                let results = simulate(&words, word, &options)?;
+                match args.format {
+                    OutputFormat::Text if args.animate => {
+                        animate_sim(&results, Duration::from_millis(args.delay))?;
+                        print_sim_results(&results, false);
+                    }
+                    OutputFormat::Text => print_sim_results(&results, args.detailed),
+                    OutputFormat::Json => println!("{}", serde_json::to_string(&results)?),
+                }
+                if let Some(timings) = &results.timings {
+                    print_timings(timings);
+                }
+                if let Some(path) = &args.log {
+                    write_turn_log(path, &[(word.clone(), results)])?;
+                }
+            } else {
+                let all_results = simulate_words(&words, &targets, &options)?;
+                if let OutputFormat::Json = args.format {
+                    let all_results: Vec<_> = all_results
+                        .iter()
+                        .map(|(word, results)| WordSimResults { word, results })
+                        .collect();
+                    println!("{}", serde_json::to_string(&all_results)?);
+                } else {
+                    for (word, results) in &all_results {
+                        if args.animate {
+                            println!("{word}:");
+                            animate_sim(results, Duration::from_millis(args.delay))?;
+                            println!();
+                        } else if args.detailed {
+                            println!("{word}:");
+                            print_sim_results(results, true);
+                            println!();
                         }
                     }
-                });
-
-                let mut writer = csv::WriterBuilder::new().from_path(args.out)?;
-                for (
-                    i,
-                    (
-                        (
+                    print_sim_summary(&all_results);
+                }
+                if args.timing {
+                    let mut timings = Timings::default();
+                    for (_, results) in &all_results {
+                        if let Some(results) = &results.timings {
+                            timings.scoring.extend(&results.scoring);
+                            timings.pruning.extend(&results.pruning);
+                        }
+                    }
+                    print_timings(&timings);
+                }
+                if let Some(path) = &args.log {
+                    write_turn_log(path, &all_results)?;
+                }
+            }
+        }
+        Command::BulkSim(args) => {
+            let options = SimOptions {
+                strategy: args.strategy,
+                score_by: config.score_by,
+                position_bias: config.position_bias,
+                max_mistakes: args.max_mistakes,
+                frequencies,
+                seed,
+                openers: config.openers.clone(),
+                bigram_threshold: config.bigram_threshold,
+                timing: false,
+                score_cache: (args.score_cache > 0 && args.naive)
+                    .then(|| Arc::new(ScoreCache::new(args.score_cache))),
+            };
+            let appending = args.resume && args.out.exists();
+            let done: HashSet<String> = if appending {
+                // an interrupted run can leave a half written row at the end, which is dropped
+                // so the word is simulated again
+                let contents = std::fs::read(&args.out)?;
+                let complete = contents
+                    .iter()
+                    .rposition(|&b| b == b'\n')
+                    .map_or(0, |i| i + 1);
+                if complete < contents.len() {
+                    File::options()
+                        .write(true)
+                        .open(&args.out)?
+                        .set_len(complete as u64)?;
+                }
+                csv::ReaderBuilder::new()
+                    .delimiter(args.delimiter)
+                    .flexible(true)
+                    .from_path(&args.out)?
+                    .records()
+                    .map(|record| Ok(record?.get(0).unwrap_or_default().to_string()))
+                    .collect::<Result<_, Err>>()?
+            } else {
+                HashSet::new()
+            };
+            let targets: Vec<_> = words
+                .iter()
+                .filter(|word| {
+                    let length = word.chars().count();
+                    args.min_length.is_none_or(|min| length >= min)
+                        && args.max_length.is_none_or(|max| length <= max)
+                        && !done.contains(*word)
+                })
+                .collect();
+            if !done.is_empty() {
+                log!(1, "Skipping {} words already in {:?}", done.len(), args.out);
+            }
+            log!(2, "Simulating {} words", targets.len());
+            let mut wins = 0;
+            let mut failures = 0;
+
+            let out = if args.force {
+                File::create(&args.out)?
+            } else if args.resume {
+                File::options().append(true).create(true).open(&args.out)?
+            } else {
+                File::create_new(&args.out).map_err(|e| match e.kind() {
+                    ErrorKind::AlreadyExists => format!(
+                        "{:?} already exists, pass --force to overwrite it or --resume to add the \
+                         words it's missing",
+                        args.out
+                    )
+                    .into(),
+                    _ => Err::from(e),
+                })?
+            };
+            let mut writer = csv::WriterBuilder::new()
+                .delimiter(args.delimiter)
+                .has_headers(!appending || out.metadata()?.len() == 0)
+                .from_writer(out);
+            let mut compared = HashMap::new();
+            if let Some(strategy) = args.compare_strategy {
+                log!(1, "Simulating with the strategy to compare against");
+                let options = SimOptions {
+                    strategy,
+                    ..options.clone()
+                };
+                simulate_all(
+                    &words,
+                    &targets,
+                    &options,
+                    args.jobs,
+                    !args.naive,
+                    |word, results| {
+                        let results = results.ok();
+                        compared.insert(
                             word,
-                            SimResults {
-                                history, mistakes, ..
+                            CompareRecord {
+                                guesses: results.as_ref().map(|results| results.history.len()),
+                                mistakes: results.as_ref().map(|results| results.mistakes),
                             },
-                        ),
-                        log,
-                    ),
-                ) in (recv.into_iter().take(words.len()))
-                    .zip(Observer::new(Duration::from_secs_f32(0.1)))
-                    .enumerate()
-                {
-                    if log {
-                        reprint!("{}/{}", i, words.len());
+                        );
+                        Ok(())
+                    },
+                )?;
+                log!(1, "");
+            }
+            let mut rows = Vec::new();
+            let mut guess_counts = Vec::new();
+            let mut mistake_counts = Vec::new();
+            simulate_all(
+                &words,
+                &targets,
+                &options,
+                args.jobs,
+                !args.naive,
+                |word, results| {
+                    match &results {
+                        Ok(results) if results.won => wins += 1,
+                        Ok(_) => {}
+                        Err(_) => failures += 1,
+                    }
+                    let compare = compared.get(&word).copied();
+                    let row = SimRecord::new(word, results);
+                    guess_counts.extend(row.guesses);
+                    mistake_counts.extend(row.mistakes);
+                    if args.sort {
+                        rows.push((row, compare));
+                    } else {
+                        write_sim_row(&mut writer, row, compare)?;
                     }
-                    let row = SimRecord(word.clone(), history.len(), mistakes);
-                    writer.serialize(row)?;
+                    Ok(())
+                },
+            )?;
+            rows.sort_by_key(|(row, _)| Reverse(row.difficulty));
+            for (row, compare) in rows {
+                write_sim_row(&mut writer, row, compare)?;
+            }
+            log!(1, "\nDone");
+            if let Some(cache) = &options.score_cache {
+                let lookups = cache.hits() + cache.misses();
+                if lookups > 0 {
+                    log!(
+                        1,
+                        "Score cache: {} hits, {} misses ({:.1}% hit rate)",
+                        cache.hits(),
+                        cache.misses(),
+                        cache.hits() as f64 / lookups as f64 * 100.0
+                    );
                 }
-                Ok(())
-            })?;
-            println!("Done");
+            }
+            if args.summary.unwrap_or_else(|| stdout().is_terminal()) {
+                print_histogram("guesses", &mut guess_counts);
+                print_histogram("mistakes", &mut mistake_counts);
+            }
+            if failures > 0 {
+                println!("{failures} words failed to simulate");
+            }
+            if args.max_mistakes.is_some() && !targets.is_empty() {
+                println!(
+                    "Win rate: {:.1}%",
+                    wins as f64 / targets.len() as f64 * 100.0
+                );
+            }
+        }
+        Command::Bench(args) => {
+            let targets: Vec<_> = words.iter().collect();
+            let mut writer = args
+                .out
+                .map(|out| csv::WriterBuilder::new().from_path(out))
+                .transpose()?;
+            let mut summaries = Vec::new();
+            for &strategy in &args.strategies {
+                let name = strategy.to_possible_value().unwrap().get_name().to_string();
+                log!(1, "Simulating {} words with {name}", targets.len());
+                let options = SimOptions {
+                    strategy,
+                    score_by: config.score_by,
+                    position_bias: config.position_bias,
+                    max_mistakes: args.max_mistakes,
+                    frequencies: frequencies.clone(),
+                    seed,
+                    openers: config.openers.clone(),
+                    bigram_threshold: config.bigram_threshold,
+                    timing: false,
+                    score_cache: None,
+                };
+                let mut summary = BenchSummary::default();
+                simulate_all(
+                    &words,
+                    &targets,
+                    &options,
+                    args.jobs,
+                    false,
+                    |word, results| {
+                        summary.add(&word, &results);
+                        if let Some(writer) = &mut writer {
+                            writer.serialize((
+                                BenchStrategy { strategy: &name },
+                                SimRecord::new(word, results),
+                            ))?;
+                        }
+                        Ok(())
+                    },
+                )?;
+                log!(1, "");
+                summaries.push((name, summary));
+            }
+            print_bench_summary(&summaries, args.max_mistakes.is_some());
+        }
+        Command::Analyze(args) => {
+            if let Some(path) = &args.precompute_openers {
+                let mut openers = Openers::new();
+                for &strategy in Strategy::value_variants() {
+                    let options = SimOptions {
+                        strategy,
+                        score_by: config.score_by,
+                        position_bias: config.position_bias,
+                        frequencies: frequencies.clone(),
+                        ..Default::default()
+                    };
+                    openers.insert(
+                        strategy,
+                        compute_openers(&words, args.num_openers, &options)?,
+                    );
+                }
+                serde_json::to_writer(BufWriter::new(File::create(path)?), &openers)?;
+                log!(1, "Saved opening guesses to {path:?}");
+            }
+            let stats = analyze(&words);
+            for LengthStats {
+                length,
+                words,
+                top_first_letter,
+                letter_counts,
+            } in stats.iter()
+            {
+                println!(
+                    "{length} letters: {words} words, most common first letter: {}",
+                    top_first_letter.map_or("-".to_string(), String::from)
+                );
+                println!(
+                    "  letter frequencies: {}",
+                    letter_counts
+                        .iter()
+                        .filter(|(_, count)| *count > 0)
+                        .map(|(letter, count)| format!(
+                            "{letter} {:.1}%",
+                            *count as f64 / *words as f64 * 100.0
+                        ))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+            }
+
+            if let Some(out) = args.out {
+                let mut writer = csv::WriterBuilder::new().from_path(out)?;
+                writer.write_record(
+                    ["length", "words", "top_first_letter"]
+                        .map(String::from)
+                        .into_iter()
+                        .chain(alphabet(&words).into_iter().map(String::from)),
+                )?;
+                for stats in stats {
+                    let mut letter_counts = stats.letter_counts;
+                    letter_counts.sort();
+                    writer.write_record(
+                        [
+                            stats.length.to_string(),
+                            stats.words.to_string(),
+                            stats.top_first_letter.map(String::from).unwrap_or_default(),
+                        ]
+                        .into_iter()
+                        .chain(letter_counts.iter().map(|(_, count)| count.to_string())),
+                    )?;
+                }
+            }
+        }
+        Command::Search(args) => {
+            let pattern = parse_pattern(&args.pattern);
+            let exclude: Vec<_> = args.exclude.chars().collect();
+            let include: Vec<_> = args.include.chars().collect();
+            let matches = search(&words, &pattern, &exclude, &include);
+            for word in &matches {
+                println!("{word}");
+            }
+            println!("{} matching words", matches.len());
+        }
+        Command::Evil(args) => evil(&words, &args)?,
+        Command::Practice(args) => {
+            let options = SimOptions {
+                strategy: args.strategy,
+                score_by: config.score_by,
+                position_bias: config.position_bias,
+                max_mistakes: args.max_mistakes,
+                frequencies,
+                seed,
+                openers: config.openers.clone(),
+                bigram_threshold: config.bigram_threshold,
+                timing: false,
+                score_cache: None,
+            };
+            practice(&words, &args, &options)?;
+        }
+        Command::SelfTest(args) => {
+            let options = SimOptions {
+                strategy: args.strategy,
+                score_by: config.score_by,
+                position_bias: config.position_bias,
+                frequencies,
+                seed,
+                openers: config.openers.clone(),
+                bigram_threshold: config.bigram_threshold,
+                ..Default::default()
+            };
+            let mut rng: StdRng = match seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => rand::make_rng(),
+            };
+            let targets = sample_words(
+                &words,
+                args.sample,
+                args.weighted_random,
+                &options.frequencies,
+                &mut rng,
+            )?;
+            log!(1, "Solving {} words", targets.len());
+            let mut failures = Vec::new();
+            // every word is replayed on its own, so this checks the same code a real game runs
+            simulate_all(
+                &words,
+                &targets,
+                &options,
+                args.jobs,
+                false,
+                |word, results| {
+                    match results {
+                        Ok(results) if results.won => {}
+                        Ok(_) => failures.push((word, "lost".to_string())),
+                        Err(e) => failures.push((word, e)),
+                    }
+                    Ok(())
+                },
+            )?;
+            log!(1, "");
+            failures.sort();
+            for (word, e) in &failures {
+                println!("{word}: {e}");
+            }
+            if !failures.is_empty() {
+                Err(format!(
+                    "{} of {} words failed to solve",
+                    failures.len(),
+                    targets.len()
+                ))?;
+            }
+            println!("All {} words solved", targets.len());
+        }
+        Command::Tree(args) => {
+            let options = SimOptions {
+                strategy: args.strategy,
+                score_by: config.score_by,
+                position_bias: config.position_bias,
+                frequencies,
+                seed,
+                openers: config.openers.clone(),
+                bigram_threshold: config.bigram_threshold,
+                ..Default::default()
+            };
+            let tree = guess_tree(&words, args.length, &options, args.max_depth)?;
+            match args.format {
+                OutputFormat::Text => print_guess_tree(&tree, None, 0),
+                OutputFormat::Json => println!("{}", serde_json::to_string(&tree)?),
+            }
+        }
+        Command::Opening(args) => {
+            let options = SimOptions {
+                strategy: args.strategy,
+                score_by: config.score_by,
+                position_bias: config.position_bias,
+                frequencies,
+                seed,
+                openers: config.openers.clone(),
+                bigram_threshold: config.bigram_threshold,
+                ..Default::default()
+            };
+            let join = |guesses: &[char]| {
+                guesses
+                    .iter()
+                    .map(char::to_string)
+                    .collect::<Vec<_>>()
+                    .join(",")
+            };
+            println!(
+                "if every guess misses: {}",
+                join(&miss_guesses(&words, args.length, &options)?)
+            );
+            let player = config.apply(HangmanPlayer::new(&words, args.length)?, args.strategy);
+            let typical = player.most_likely_word().unwrap().to_string();
+            let results = simulate(&words, &typical, &options)?;
+            println!(
+                "against {typical}, the most likely word: {}",
+                join(&results.guesses)
+            );
         }
+        Command::Replay(args) => replay(&words, &args, &config)?,
+        Command::Serve(args) => serve::serve(words, args, config)?,
     }
 
     Ok(())
@@ -0,0 +1,222 @@
+use std::{collections::HashMap, sync::atomic::Ordering, time::Instant};
+
+use hangman::{HangmanPlayer, Strategy};
+use serde::{Deserialize, Serialize};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::{Err, PlayerConfig, ServeArgs, VERBOSITY};
+
+#[derive(Deserialize)]
+struct NewRequest {
+    length: usize,
+}
+
+#[derive(Deserialize)]
+struct GuessRequest {
+    session: u64,
+    letter: char,
+    /// One-indexed positions the letter appears at, empty if it isn't in the word
+    #[serde(default)]
+    positions: Vec<usize>,
+}
+
+#[derive(Serialize)]
+struct SessionState<'a> {
+    session: u64,
+    current_guess: &'a [Option<char>],
+    not_present: &'a [char],
+    used_letters: &'a [char],
+    remaining: usize,
+    suggestions: Vec<(char, usize)>,
+    /// The answer, once only one word remains
+    word: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct ApiError {
+    #[serde(skip)]
+    status: u16,
+    error: String,
+}
+
+impl ApiError {
+    fn new(status: u16, error: impl ToString) -> ApiError {
+        ApiError {
+            status,
+            error: error.to_string(),
+        }
+    }
+}
+
+struct Session {
+    player: HangmanPlayer,
+    last_used: Instant,
+}
+
+struct Sessions {
+    words: Vec<String>,
+    config: PlayerConfig,
+    num_suggestions: usize,
+    max_sessions: usize,
+    sessions: HashMap<u64, Session>,
+}
+
+impl Sessions {
+    fn player(&mut self, session: u64) -> Result<&mut HangmanPlayer, ApiError> {
+        let session = self
+            .sessions
+            .get_mut(&session)
+            .ok_or_else(|| ApiError::new(404, format!("No session with id {session}")))?;
+        session.last_used = Instant::now();
+        Ok(&mut session.player)
+    }
+
+    fn state(&mut self, session: u64) -> Result<String, ApiError> {
+        let num_suggestions = self.num_suggestions;
+        let player = self.player(session)?;
+        let state = SessionState {
+            session,
+            current_guess: player.current_guess(),
+            not_present: player.not_present(),
+            used_letters: player.used_letters(),
+            remaining: player.available_words().len(),
+            suggestions: player.best_guesses(num_suggestions),
+            word: match player.available_words() {
+                [word] => Some(word),
+                _ => None,
+            },
+        };
+        serde_json::to_string(&state).map_err(|e| ApiError::new(500, e))
+    }
+
+    fn new_session(&mut self, request: NewRequest) -> Result<String, ApiError> {
+        if request.length == 0 {
+            return Err(ApiError::new(400, "Length must be at least 1!"));
+        }
+        let player = self.config.apply(
+            HangmanPlayer::new(&self.words, request.length).map_err(|e| ApiError::new(400, e))?,
+            Strategy::Frequency,
+        );
+        if self.sessions.len() >= self.max_sessions {
+            let oldest = self
+                .sessions
+                .iter()
+                .min_by_key(|(_, session)| session.last_used)
+                .map(|(&id, _)| id);
+            if let Some(oldest) = oldest {
+                log!(1, "Too many sessions, dropping session {oldest}");
+                self.sessions.remove(&oldest);
+            }
+        }
+        // random so one client can't guess another's session, and kept to 53 bits so the id
+        // survives being read as a javascript number
+        let session = loop {
+            let session = rand::random::<u64>() >> 11;
+            if !self.sessions.contains_key(&session) {
+                break session;
+            }
+        };
+        self.sessions.insert(
+            session,
+            Session {
+                player,
+                last_used: Instant::now(),
+            },
+        );
+        self.state(session)
+    }
+
+    fn end_session(&mut self, session: u64) -> Result<String, ApiError> {
+        self.sessions
+            .remove(&session)
+            .ok_or_else(|| ApiError::new(404, format!("No session with id {session}")))?;
+        Ok("{}".into())
+    }
+
+    fn guess(&mut self, request: GuessRequest) -> Result<String, ApiError> {
+        let player = self.player(request.session)?;
+        let letter = match request.letter.to_lowercase().collect::<Vec<_>>()[..] {
+            [letter] => letter,
+            ref folded => {
+                return Err(ApiError::new(
+                    400,
+                    format!(
+                        "{} lowercases to {}, which isn't a single letter",
+                        request.letter,
+                        folded.iter().collect::<String>()
+                    ),
+                ))
+            }
+        };
+        if player.used_letters().contains(&letter) {
+            return Err(ApiError::new(
+                400,
+                format!("{letter} has already been guessed"),
+            ));
+        }
+        if request.positions.contains(&0) {
+            return Err(ApiError::new(400, "Positions start at 1"));
+        }
+        player
+            .guess_letter(letter, request.positions.iter().map(|p| p - 1).collect())
+            .map_err(|e| ApiError::new(400, e))?;
+        self.state(request.session)
+    }
+
+    fn handle(&mut self, request: &mut Request) -> Result<String, ApiError> {
+        let mut body = String::new();
+        request
+            .as_reader()
+            .read_to_string(&mut body)
+            .map_err(|e| ApiError::new(400, e))?;
+        let parse_error = |e: serde_json::Error| ApiError::new(400, e);
+        match (request.method(), request.url()) {
+            (Method::Post, "/new") => {
+                self.new_session(serde_json::from_str(&body).map_err(parse_error)?)
+            }
+            (Method::Post, "/guess") => {
+                self.guess(serde_json::from_str(&body).map_err(parse_error)?)
+            }
+            (method @ (Method::Get | Method::Delete), url) if url.starts_with("/state/") => {
+                let session = url["/state/".len()..]
+                    .parse()
+                    .map_err(|_| ApiError::new(400, "Session id must be a number"))?;
+                match method {
+                    Method::Delete => self.end_session(session),
+                    _ => self.state(session),
+                }
+            }
+            _ => Err(ApiError::new(404, "Not found")),
+        }
+    }
+}
+
+/// Serve the solver over http, answering requests until the process is killed
+pub fn serve(words: Vec<String>, args: ServeArgs, config: PlayerConfig) -> Result<(), Err> {
+    let server = Server::http(&args.address).map_err(|e| e.to_string())?;
+    log!(1, "Listening on http://{}", args.address);
+    let mut sessions = Sessions {
+        words,
+        config,
+        num_suggestions: args.num_suggestions,
+        max_sessions: args.max_sessions,
+        sessions: HashMap::new(),
+    };
+    let content_type: Header = "Content-Type: application/json".parse().unwrap();
+
+    for mut request in server.incoming_requests() {
+        let (status, body) = match sessions.handle(&mut request) {
+            Ok(body) => (200, body),
+            Err(error) => (error.status, serde_json::to_string(&error)?),
+        };
+        if let Err(e) = request.respond(
+            Response::from_string(body)
+                .with_status_code(status)
+                .with_header(content_type.clone()),
+        ) {
+            log!(1, "Couldn't send a response: {e}");
+        }
+    }
+
+    Ok(())
+}
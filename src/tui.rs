@@ -0,0 +1,548 @@
+use hangman::{is_lost, Strategy};
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Wrap},
+    DefaultTerminal, Frame,
+};
+
+use crate::{
+    gallows, nonzero, reveal_question, score_table, Bits, Err, HitChance, InputMode, PlayerUI,
+    Precomputed, WorstCase, BOARD_HELPTEXT, COMMAND_HELPTEXT, GUESS_HELPTEXT, RESET_QUESTION,
+};
+
+const KEY_HELPTEXT: &str =
+    "Press ctrl-z to undo, ctrl-y to redo, ctrl-c to save the game and quit, and esc to quit";
+
+/// How the game stands once it's no longer waiting for a guess
+enum Ending {
+    /// Only this word is left, and `--confirm` asks whether it really is the word
+    Confirm(String),
+    /// `reveal` was typed while other words are still possible, so it asks whether to give up
+    /// with this one
+    Reveal(String),
+    /// `reset` was typed, so it asks whether to clear every guess
+    Reset,
+    /// The game is over, with the word if it was found or why it was lost
+    Over(Result<String, String>),
+}
+
+struct Tui<'u, 'a> {
+    ui: &'u mut PlayerUI<'a>,
+    input: String,
+    /// Result of the last thing typed
+    messages: Vec<String>,
+    /// Letter suggestions for the current board, worked out once per turn rather than every frame
+    suggestions: Vec<(char, String)>,
+    show_help: bool,
+    /// Show the score of every letter in place of the board info
+    show_scores: bool,
+    /// List every possible word, not only once there are few enough of them
+    show_words: bool,
+    /// Ctrl-C was pressed, so the input box takes a path to save the game to before quitting
+    saving: bool,
+    ending: Option<Ending>,
+}
+
+/// Play the game in a full screen interface laid out with ratatui, until the word is found or the
+/// user quits. Returns the word, or `None` if the user quit first.
+pub fn play(ui: &mut PlayerUI) -> Result<Option<String>, Err> {
+    let mut tui = Tui {
+        ui,
+        input: String::new(),
+        messages: vec![],
+        suggestions: vec![],
+        show_help: false,
+        show_scores: false,
+        show_words: false,
+        saving: false,
+        ending: None,
+    };
+    tui.refresh();
+    ratatui::run(|terminal| tui.run(terminal))
+}
+
+impl Tui<'_, '_> {
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<Option<String>, Err> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            if let Some(Ending::Over(result)) = &self.ending {
+                return result.clone().map(Some).map_err(Err::from);
+            }
+            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+            match key.code {
+                KeyCode::Esc => return Ok(None),
+                KeyCode::Char('c') if ctrl && self.saving => return Ok(None),
+                KeyCode::Char('c') if ctrl => {
+                    self.input.clear();
+                    self.messages = vec![
+                        "Save game before quitting? Type a path to save to, or hit enter to quit"
+                            .to_string(),
+                    ];
+                    self.saving = true;
+                }
+                KeyCode::Char('z') if ctrl => self.undo(),
+                KeyCode::Char('y') if ctrl => self.redo(),
+                KeyCode::Char(c) if !ctrl => self.input.push(c),
+                KeyCode::Enter if self.saving => {
+                    if self.save_and_quit() {
+                        return Ok(None);
+                    }
+                }
+                KeyCode::Enter => self.submit(),
+                KeyCode::Backspace => {
+                    self.input.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn undo(&mut self) {
+        self.messages.clear();
+        if self.ui.player.history().is_empty() {
+            self.messages.push("Nothing to undo!".to_string());
+            return;
+        }
+        self.ui.undo();
+        self.refresh();
+    }
+
+    fn redo(&mut self) {
+        self.messages.clear();
+        if self.ui.redo_stack.is_empty() {
+            self.messages.push("Nothing to redo!".to_string());
+            return;
+        }
+        self.ui.redo();
+        self.refresh();
+    }
+
+    /// Save the game to the path typed into the input box, if any. Returns whether to quit, which
+    /// is unless saving failed.
+    fn save_and_quit(&mut self) -> bool {
+        let path = std::mem::take(&mut self.input);
+        let path = path.trim();
+        if path.is_empty() {
+            return true;
+        }
+        match self.ui.save(path.as_ref()) {
+            Ok(()) => true,
+            Err(e) => {
+                self.messages = vec![
+                    format!("Failed to save game: {e}"),
+                    "Type another path to save to, or hit enter to quit".to_string(),
+                ];
+                false
+            }
+        }
+    }
+
+    /// Act on the line typed into the input box
+    fn submit(&mut self) {
+        let raw = std::mem::take(&mut self.input);
+        let command = raw.trim().to_lowercase();
+        self.messages.clear();
+        self.show_help = false;
+        self.show_scores = false;
+
+        if let Some(Ending::Confirm(word)) = &self.ending {
+            let word = word.clone();
+            match command.as_str() {
+                "" | "y" | "yes" => self.ending = Some(Ending::Over(Ok(word))),
+                "n" | "no" => {
+                    self.ui.player.reject_word(&word);
+                    self.ui.redo_stack.clear();
+                    self.messages.push(format!("Ruled out {word}"));
+                    self.refresh();
+                }
+                _ => self.messages.push("Type y or n".to_string()),
+            }
+            return;
+        }
+
+        if let Some(Ending::Reveal(word)) = &self.ending {
+            let word = word.clone();
+            self.ending = None;
+            match command.as_str() {
+                "y" | "yes" => {
+                    self.messages
+                        .push(format!("Giving up, the most likely word was {word}"));
+                    self.ending = Some(Ending::Over(Ok(word)));
+                }
+                "" | "n" | "no" => {}
+                _ => {
+                    self.messages.push("Type y or n".to_string());
+                    self.ending = Some(Ending::Reveal(word));
+                }
+            }
+            return;
+        }
+
+        if let Some(Ending::Reset) = &self.ending {
+            self.ending = None;
+            match command.as_str() {
+                "y" | "yes" => {
+                    self.ui.previous_scores.clear();
+                    self.ui.player.reset();
+                    self.ui.redo_stack.clear();
+                    self.messages.push("Cleared every guess".to_string());
+                    self.refresh();
+                }
+                "" | "n" | "no" => {}
+                _ => {
+                    self.messages.push("Type y or n".to_string());
+                    self.ending = Some(Ending::Reset);
+                }
+            }
+            return;
+        }
+
+        if let Some(path) = raw.trim().strip_prefix("save ") {
+            self.messages
+                .push(match self.ui.save(path.trim().as_ref()) {
+                    Ok(()) => format!("Game saved to {}", path.trim()),
+                    Err(e) => format!("Failed to save game: {e}"),
+                });
+            return;
+        }
+        if let Some(length) = command.strip_prefix("new ") {
+            match nonzero(length.trim())
+                .map_err(Err::from)
+                .and_then(|length| {
+                    self.ui.restart(length)?;
+                    Ok(length)
+                }) {
+                Ok(length) => self
+                    .messages
+                    .push(format!("Guessing a new {length}-letter word")),
+                Err(e) => self
+                    .messages
+                    .push(format!("Couldn't start a new word: {e}")),
+            }
+            self.refresh();
+            return;
+        }
+        match command.as_str() {
+            "" => self.show_help = true,
+            "undo" => self.undo(),
+            "redo" => self.redo(),
+            "words" => self.show_words = !self.show_words,
+            "scores" => self.show_scores = true,
+            "reset" => self.ending = Some(Ending::Reset),
+            "reveal" => match self.ui.reveal() {
+                Some((word, None)) => {
+                    self.messages
+                        .push(format!("Giving up, the most likely word was {word}"));
+                    self.ending = Some(Ending::Over(Ok(word)));
+                }
+                Some((word, Some(remaining))) => {
+                    self.messages.push(remaining);
+                    self.ending = Some(Ending::Reveal(word));
+                }
+                None => self
+                    .messages
+                    .push("No words are left to reveal".to_string()),
+            },
+            _ => match self.ui.parse_guess(&raw) {
+                Ok(guesses) => {
+                    self.messages = self.ui.apply_guesses(guesses);
+                    self.refresh();
+                }
+                Err(message) => self.messages.push(message),
+            },
+        }
+    }
+
+    /// Work out the suggestions for the board as it is now, and whether the game has ended
+    fn refresh(&mut self) {
+        self.ending = None;
+        self.suggestions = self.suggestions();
+        let player = &self.ui.player;
+        if let Some(max_mistakes) = self.ui.args.max_mistakes {
+            if is_lost(player.not_present().len(), Some(max_mistakes)) {
+                self.ending = Some(Ending::Over(Err(format!(
+                    "Made {max_mistakes} mistakes, the game is lost!"
+                ))));
+                return;
+            }
+        }
+        match player.available_words() {
+            [word] if self.ui.args.confirm => self.ending = Some(Ending::Confirm(word.clone())),
+            [word] => self.ending = Some(Ending::Over(Ok(word.clone()))),
+            [] => {
+                let closest = player.closest_words(self.ui.args.num_closest);
+                if !closest.is_empty() {
+                    self.messages
+                        .push(format!("Did you mean: {}?", closest.join(", ")));
+                }
+                self.messages.push(
+                    "No possible words left! is it in the database / did you make a mistake? \
+                     Press ctrl-z to undo"
+                        .to_string(),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    fn suggestions(&self) -> Vec<(char, String)> {
+        let player = &self.ui.player;
+        if player.available_words().len() <= 1 || !player.has_informative_letters() {
+            return vec![];
+        }
+        if let Some(openers) = player.openers() {
+            return openers
+                .iter()
+                .map(|&letter| (letter, Precomputed.to_string()))
+                .collect();
+        }
+        match self.ui.args.strategy {
+            Strategy::Frequency => player
+                .compute_letter_scores()
+                .into_iter()
+                .map(|(letter, score)| (letter, score.to_string()))
+                .collect(),
+            Strategy::Entropy => player
+                .compute_letter_entropies()
+                .into_iter()
+                .map(|(letter, entropy)| (letter, Bits(entropy).to_string()))
+                .collect(),
+            Strategy::Minimax => player
+                .compute_letter_minimax()
+                .into_iter()
+                .map(|(letter, worst)| (letter, WorstCase(worst).to_string()))
+                .collect(),
+            Strategy::Safe => player
+                .compute_letter_safety()
+                .into_iter()
+                .map(|(letter, safety)| (letter, HitChance(safety).to_string()))
+                .collect(),
+        }
+    }
+
+    /// `color` as a foreground style, or no style at all if color is turned off
+    fn fg(&self, color: Color) -> Style {
+        if self.ui.color {
+            Style::new().fg(color)
+        } else {
+            Style::new()
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [board, middle, messages, input] = Layout::vertical([
+            Constraint::Length(5),
+            Constraint::Min(6),
+            Constraint::Length(6),
+            Constraint::Length(3),
+        ])
+        .areas(frame.area());
+        let [suggestions, info] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(middle);
+        self.draw_board(frame, board);
+        self.draw_suggestions(frame, suggestions);
+        self.draw_info(frame, info);
+        self.draw_messages(frame, messages);
+        self.draw_input(frame, input);
+    }
+
+    /// Each letter of the word in a box of its own
+    fn draw_board(&self, frame: &mut Frame, area: Rect) {
+        let current = self.ui.player.current_guess();
+        let block = Block::bordered().title(format!(" {}-letter word ", current.len()));
+        let cells =
+            Layout::horizontal(vec![Constraint::Length(5); current.len()]).split(block.inner(area));
+        frame.render_widget(block, area);
+        for (letter, cell) in current.iter().zip(cells.iter()) {
+            let (text, style) = match letter {
+                Some(letter) => (
+                    letter.to_string(),
+                    self.fg(Color::Green).add_modifier(Modifier::BOLD),
+                ),
+                None => ("_".to_string(), self.fg(Color::DarkGray)),
+            };
+            frame.render_widget(
+                Paragraph::new(text)
+                    .style(style)
+                    .centered()
+                    .block(Block::bordered()),
+                *cell,
+            );
+        }
+    }
+
+    fn draw_suggestions(&self, frame: &mut Frame, area: Rect) {
+        let player = &self.ui.player;
+        let lines: Vec<Line> = if self.suggestions.is_empty() && player.available_words().len() > 1
+        {
+            std::iter::once(Line::from(format!(
+                "No informative letters remain, {} indistinguishable candidates:",
+                player.available_words().len()
+            )))
+            .chain(player.words_by_likelihood().into_iter().map(Line::from))
+            .collect()
+        } else {
+            let words = player.available_words();
+            self.suggestions
+                .iter()
+                .take(self.ui.args.num_suggestions)
+                .enumerate()
+                .map(|(i, (letter, score))| {
+                    let containing = words.iter().filter(|w| w.contains(*letter)).count();
+                    let line = Line::from(format!(
+                        "{}. {letter}: {score} ({:.1}%)",
+                        i + 1,
+                        containing as f64 / words.len() as f64 * 100.0
+                    ));
+                    if i == 0 {
+                        line.style(self.fg(Color::Yellow).add_modifier(Modifier::BOLD))
+                    } else {
+                        line
+                    }
+                })
+                .collect()
+        };
+        frame.render_widget(
+            Paragraph::new(lines)
+                .wrap(Wrap { trim: false })
+                .block(Block::bordered().title(" suggestions ")),
+            area,
+        );
+    }
+
+    /// What's known about the word so far, or the help text once asked for
+    fn draw_info(&self, frame: &mut Frame, area: Rect) {
+        let player = &self.ui.player;
+        let mut lines = vec![];
+        if self.show_help {
+            let helptext = match self.ui.args.input_mode {
+                InputMode::Incremental => GUESS_HELPTEXT,
+                InputMode::Board => BOARD_HELPTEXT,
+            };
+            lines.extend(
+                [helptext, COMMAND_HELPTEXT, KEY_HELPTEXT]
+                    .into_iter()
+                    .flat_map(str::lines)
+                    .map(Line::from),
+            );
+        } else if self.show_scores {
+            if self.suggestions.is_empty() {
+                lines.push(Line::from("No letters to score this turn"));
+            }
+            let width = area.width.saturating_sub(2) as usize;
+            lines.extend(
+                score_table(&self.suggestions, width)
+                    .into_iter()
+                    .map(Line::from),
+            );
+        } else {
+            if !player.not_present().is_empty() {
+                let mut spans = vec![Span::raw("letters not present:")];
+                for letter in player.not_present() {
+                    spans.push(Span::raw(" "));
+                    spans.push(Span::styled(letter.to_string(), self.fg(Color::Red)));
+                }
+                lines.push(Line::from(spans));
+            }
+            lines.push(Line::from(format!(
+                "{} possible words",
+                player.available_words().len()
+            )));
+            lines.push(Line::from(format!(
+                "approx {:.1} guesses remaining",
+                player.estimated_guesses_remaining()
+            )));
+            if let Some(word) = player.most_likely_word() {
+                lines.push(Line::from(format!("most likely: {word}")));
+            }
+            if self.ui.args.show_positions {
+                lines.extend(self.ui.likely_letters().map(Line::from));
+            }
+            if let Some(max_mistakes) = self.ui.args.max_mistakes {
+                let mistakes = player.not_present().len();
+                lines.extend(
+                    gallows(mistakes.min(max_mistakes), max_mistakes)
+                        .lines()
+                        .map(|line| Line::from(line.to_string())),
+                );
+                lines.push(Line::from(format!("mistakes: {mistakes}/{max_mistakes}")));
+            }
+            if !player.available_words().is_empty()
+                && (self.show_words
+                    || player.available_words().len() <= self.ui.args.display_guesses_threshold)
+            {
+                lines.push(Line::from("Possibilities:"));
+                lines.extend(player.words_by_likelihood().into_iter().map(Line::from));
+            }
+        }
+        frame.render_widget(
+            Paragraph::new(lines)
+                .wrap(Wrap { trim: false })
+                .block(Block::bordered().title(if self.show_help {
+                    " help "
+                } else if self.show_scores {
+                    " scores "
+                } else {
+                    " board "
+                })),
+            area,
+        );
+    }
+
+    fn draw_messages(&self, frame: &mut Frame, area: Rect) {
+        let mut lines: Vec<Line> = self
+            .messages
+            .iter()
+            .map(|m| Line::from(m.as_str()))
+            .collect();
+        match &self.ending {
+            Some(Ending::Confirm(word)) => lines.push(Line::from(format!(
+                "Only {word} is left, is that the word? [Y/n]"
+            ))),
+            Some(Ending::Reveal(word)) => {
+                lines.push(Line::from(format!("{} [y/N]", reveal_question(word))))
+            }
+            Some(Ending::Reset) => lines.push(Line::from(format!("{RESET_QUESTION} [y/N]"))),
+            Some(Ending::Over(result)) => {
+                let line = match result {
+                    Ok(word) => {
+                        Line::from(format!("The word is {word}")).style(self.fg(Color::Green))
+                    }
+                    Err(e) => Line::from(e.as_str()).style(self.fg(Color::Red)),
+                };
+                lines.push(line);
+                lines.push(Line::from("Press any key to exit"));
+            }
+            None => {}
+        }
+        frame.render_widget(
+            Paragraph::new(lines)
+                .wrap(Wrap { trim: false })
+                .block(Block::bordered()),
+            area,
+        );
+    }
+
+    fn draw_input(&self, frame: &mut Frame, area: Rect) {
+        let prompt = match self.ui.args.input_mode {
+            _ if self.saving => " path to save the game to, enter to quit without saving ",
+            InputMode::Incremental => " letter you guessed and where it's at, enter for help ",
+            InputMode::Board => " board as it is now and any wrong letters, enter for help ",
+        };
+        frame.render_widget(
+            Paragraph::new(format!("> {}", self.input)).block(Block::bordered().title(prompt)),
+            area,
+        );
+        frame.set_cursor_position((area.x + 3 + self.input.chars().count() as u16, area.y + 1));
+    }
+}
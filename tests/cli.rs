@@ -0,0 +1,201 @@
+use std::{
+    fs,
+    io::Write,
+    path::PathBuf,
+    process::{Command, Output, Stdio},
+};
+
+/// Write `words` to a word list in the test scratch directory, named after the test using it
+fn word_list(name: &str, words: &[&str]) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(format!("{name}.txt"));
+    fs::write(&path, words.join("\n")).unwrap();
+    path
+}
+
+/// Run the binary with `args`, feeding it `input` on stdin
+fn run(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_hangman"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn positions_past_known_pattern_are_rejected() {
+    let words = word_list("positions_known", &["cat", "bat", "cot"]);
+    let output = run(
+        &["-f", words.to_str().unwrap(), "play", "--known", "_a_"],
+        "t 4\nc 1\n",
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("Positions provided are invalid letter indicies"));
+    assert!(stdout.contains("Final guess: cat"));
+}
+
+#[test]
+fn positions_past_prompted_length_are_rejected() {
+    let words = word_list("positions_prompted", &["cat", "cot", "dog"]);
+    let output = run(&["-f", words.to_str().unwrap(), "play"], "3\na 4\na 2\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("Positions provided are invalid letter indicies"));
+    assert!(stdout.contains("Final guess: cat"));
+}
+
+#[test]
+fn known_pattern_is_lowercased() {
+    let words = word_list("known_lowercased", &["apple", "ample", "angle"]);
+    let output = run(
+        &["-f", words.to_str().unwrap(), "play", "--known", "A____"],
+        "p 2 3\n",
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("Final guess: apple"));
+}
+
+#[test]
+fn known_pattern_matching_nothing_is_an_error() {
+    let words = word_list("known_nothing", &["apple", "ample", "angle"]);
+    let output = run(
+        &["-f", words.to_str().unwrap(), "play", "--known", "zz___"],
+        "",
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("No 5-letter word matches the known letters zz___"));
+}
+
+#[test]
+fn play_with_words_from_stdin_needs_the_tui() {
+    let output = run(&["-f", "-", "play", "--auto"], "cat\ncot\ndog\n");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("it needs --tui"));
+}
+
+#[test]
+fn oversized_position_reprompts() {
+    let words = word_list("oversized_position", &["cat", "cot", "dog"]);
+    let output = run(
+        &["-f", words.to_str().unwrap(), "play", "3"],
+        "a 99999999999999999999\na 2\n",
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("Invalid position number `99999999999999999999`"));
+    assert!(stdout.contains("Final guess: cat"));
+}
+
+#[test]
+fn capitalized_words_are_guessed_in_lowercase() {
+    let words = word_list("capitalized", &["Apple", "Ample", "Angle"]);
+    let output = run(&["-f", words.to_str().unwrap(), "play", "5"], "p 2 3\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("Final guess: apple"));
+}
+
+#[test]
+fn bulk_sim_keeps_existing_output_without_force() {
+    let words = word_list("existing_output", &["cat", "cot", "dog"]);
+    let out = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("existing_output.csv");
+    fs::write(&out, "keep me\n").unwrap();
+    let args = [
+        "-f",
+        words.to_str().unwrap(),
+        "bulk-sim",
+        "-o",
+        out.to_str().unwrap(),
+    ];
+    let output = run(&args, "");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("already exists, pass --force"));
+    assert_eq!(fs::read_to_string(&out).unwrap(), "keep me\n");
+
+    let output = run(&[&args[..], &["--force"]].concat(), "");
+    assert!(output.status.success());
+    assert_ne!(fs::read_to_string(&out).unwrap(), "keep me\n");
+}
+
+#[test]
+fn guess_positions_accept_repeated_spaces() {
+    let words = word_list("repeated_spaces", &["level", "lever", "label"]);
+    let output = run(&["-f", words.to_str().unwrap(), "play", "5"], "e  2 4\nr\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("Letter e is at position(s) 2, 4 of the word"));
+    assert!(stdout.contains("Final guess: level"));
+}
+
+#[test]
+fn guess_positions_accept_tabs() {
+    let words = word_list("tab_separated", &["bet", "bat", "bit"]);
+    let output = run(&["-f", words.to_str().unwrap(), "play", "3"], "e\t2\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("Final guess: bet"));
+}
+
+#[test]
+fn guess_file_without_trailing_newline_finishes() {
+    let words = word_list("no_trailing_newline", &["cat", "cot", "dog"]);
+    let output = run(&["-f", words.to_str().unwrap(), "play", "3"], "a 2");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("Final guess: cat"));
+}
+
+#[test]
+fn guess_file_ending_early_stops_the_game() {
+    let words = word_list("ending_early", &["cat", "cot", "dog"]);
+    let output = run(&["-f", words.to_str().unwrap(), "play", "3"], "a");
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("Input ended before the game was over")
+    );
+}
+
+#[test]
+fn board_can_leave_inferred_letters_blank() {
+    let words = word_list("inferred_blank", &["cat", "cot", "bag"]);
+    let output = run(
+        &[
+            "-f",
+            words.to_str().unwrap(),
+            "play",
+            "3",
+            "--input-mode",
+            "board",
+        ],
+        "c__\nca_\n",
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(!stdout.contains("already known"));
+    assert!(stdout.contains("Final guess: cat"));
+}
+
+#[test]
+fn known_letters_can_repeat_in_the_blanks() {
+    let words = word_list("known_repeats", &["paper", "plant", "piano"]);
+    let output = run(
+        &["-f", words.to_str().unwrap(), "play", "--known", "p____"],
+        "p 3\n",
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("3 possible words"));
+    assert!(stdout.contains("Letter p is at position(s) 1, 3 of the word"));
+    assert!(stdout.contains("Final guess: paper"));
+}